@@ -7,6 +7,86 @@ pub struct AppConfig {
     pub mqtt: MqttConfig,
     pub wasm: WasmConfig,
     pub health: HealthConfig,
+    /// Additional input sources polled alongside the MQTT subscription, such
+    /// as a Modbus TCP bridge. Empty by default - the processor runs on MQTT
+    /// input alone unless sources are configured.
+    #[serde(default)]
+    pub sources: Vec<SourceConfig>,
+    /// InfluxDB line-protocol export of `MetricsCollector` snapshots. `None`
+    /// leaves metrics log-only, as before.
+    pub influx: Option<InfluxConfig>,
+    /// Port the pull-based Prometheus scrape endpoint (`/metrics`, only
+    /// served when the `prometheus` feature is enabled) listens on. Kept
+    /// separate from the fixed health/metrics HTTP port 8080, which already
+    /// serves its own JSON `/metrics`.
+    #[serde(default = "default_prometheus_port")]
+    pub prometheus_port: u16,
+}
+
+fn default_prometheus_port() -> u16 {
+    9090
+}
+
+/// Where to ship periodic `MetricsData` snapshots as InfluxDB line protocol.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InfluxConfig {
+    pub host: String,
+    pub database: String,
+    /// Tag identifying this processor instance in the `welding_metrics`
+    /// measurement, e.g. the MQTT client id.
+    pub machine_id: String,
+    pub export_interval_seconds: u64,
+}
+
+/// One entry of the `[[sources]]` array: an input feeding the same
+/// processing pipeline that messages arriving on `mqtt.input_topic` do.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SourceConfig {
+    /// The primary MQTT subscription is always active; this variant exists
+    /// so a `[[sources]]` array can name it explicitly alongside others.
+    Mqtt,
+    Modbus(ModbusSourceConfig),
+}
+
+/// A Modbus TCP device polled on `poll_interval_ms` for the configured
+/// registers, with each poll emitted as one JSON message into the same
+/// pipeline that the WASM quality filter processes MQTT input through.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModbusSourceConfig {
+    pub host: String,
+    pub port: u16,
+    pub unit_id: u8,
+    pub poll_interval_ms: u64,
+    /// Topic that data polled from this device is tagged with, so it flows
+    /// through the same `(topic, payload)` shape as MQTT messages.
+    pub topic: String,
+    pub registers: Vec<ModbusRegister>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModbusRegister {
+    /// JSON field name the register's value is emitted under.
+    pub name: String,
+    pub address: u16,
+    #[serde(default)]
+    pub register_type: ModbusRegisterType,
+    #[serde(default = "default_register_scale")]
+    pub scale: f64,
+    #[serde(default)]
+    pub offset: f64,
+}
+
+fn default_register_scale() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModbusRegisterType {
+    #[default]
+    Holding,
+    Input,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +98,38 @@ pub struct MqttConfig {
     pub output_topic: String,
     pub qos: u8,
     pub keep_alive: u64,
+    /// MQTT protocol version to speak on the wire: `"v4"` (3.1.1, the default)
+    /// or `"v5"`. Only v5 carries the user properties and message-expiry
+    /// interval used to route/filter alerts on metadata.
+    pub protocol_version: String,
+    /// Message-expiry interval (seconds) set on outgoing quality alerts when
+    /// `protocol_version` is `"v5"`. Ignored under v4, which has no such
+    /// property.
+    pub message_expiry_seconds: Option<u32>,
+    /// Retained topic carrying `{"status":"running"|"stopped"}`, set as the
+    /// connection's Last Will and updated on connect/shutdown so a dead
+    /// processor is visible even when nothing else is polling it.
+    pub status_topic: String,
+    /// Topic polled by supervisors to request a liveness check over MQTT
+    /// instead of the HTTP `/health` endpoint. Any message received here
+    /// triggers a `HealthService::check_health()` run.
+    pub health_check_topic: String,
+    /// Topic the resulting `HealthStatus` JSON is published to in response
+    /// to a message on `health_check_topic`.
+    pub health_status_topic: String,
+    /// TLS/mTLS transport settings. `None` keeps the plaintext connection
+    /// used against local/dev brokers.
+    pub tls: Option<TlsConfig>,
+}
+
+/// TLS transport settings for connecting to brokers that mandate TLS (e.g.
+/// Azure IoT Operations on port 8883). `client_cert_path`/`client_key_path`
+/// are only needed for mutual TLS.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub ca_cert_path: String,
+    pub client_cert_path: Option<String>,
+    pub client_key_path: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,6 +187,47 @@ impl AppConfig {
         let wasm_module_path = env::var("WASM_MODULE_PATH")
             .unwrap_or_else(|_| "wasm_quality_filter.wasm".to_string());
 
+        let protocol_version = env::var("MQTT_PROTOCOL_VERSION")
+            .unwrap_or_else(|_| "v4".to_string());
+
+        let message_expiry_seconds = env::var("MQTT_MESSAGE_EXPIRY_SECONDS")
+            .ok()
+            .map(|v| v.parse::<u32>())
+            .transpose()
+            .context("Invalid MQTT_MESSAGE_EXPIRY_SECONDS")?;
+
+        let status_topic = env::var("STATUS_TOPIC")
+            .unwrap_or_else(|_| format!("{}/status", client_id));
+
+        let health_check_topic = env::var("HEALTH_CHECK_TOPIC")
+            .unwrap_or_else(|_| format!("azure-iot-operations/health-check/{}", client_id));
+
+        let health_status_topic = env::var("HEALTH_STATUS_TOPIC")
+            .unwrap_or_else(|_| format!("azure-iot-operations/health-status/{}", client_id));
+
+        let tls = env::var("MQTT_TLS_CA_CERT").ok().map(|ca_cert_path| TlsConfig {
+            ca_cert_path,
+            client_cert_path: env::var("MQTT_TLS_CLIENT_CERT").ok(),
+            client_key_path: env::var("MQTT_TLS_CLIENT_KEY").ok(),
+        });
+
+        let influx = env::var("INFLUX_HOST").ok().map(|host| InfluxConfig {
+            host,
+            database: env::var("INFLUX_DATABASE").unwrap_or_else(|_| "welding".to_string()),
+            machine_id: client_id.clone(),
+            export_interval_seconds: env::var("INFLUX_EXPORT_INTERVAL_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30),
+        });
+
+        let prometheus_port = env::var("PROMETHEUS_PORT")
+            .ok()
+            .map(|v| v.parse::<u16>())
+            .transpose()
+            .context("Invalid PROMETHEUS_PORT")?
+            .unwrap_or_else(default_prometheus_port);
+
         let config = Self {
             mqtt: MqttConfig {
                 broker_host: mqtt_broker,
@@ -84,6 +237,12 @@ impl AppConfig {
                 output_topic,
                 qos: 1, // QoS 1 (At least once)
                 keep_alive: 60,
+                protocol_version,
+                message_expiry_seconds,
+                status_topic,
+                health_check_topic,
+                health_status_topic,
+                tls,
             },
             wasm: WasmConfig {
                 module_path: wasm_module_path,
@@ -94,6 +253,9 @@ impl AppConfig {
                 check_interval_seconds: 30,
                 unhealthy_threshold: 3,
             },
+            sources: Vec::new(),
+            influx,
+            prometheus_port,
         };
 
         Ok(config)
@@ -113,6 +275,34 @@ impl AppConfig {
             anyhow::bail!("Output topic cannot be empty");
         }
 
+        if !matches!(self.mqtt.protocol_version.as_str(), "v4" | "v5") {
+            anyhow::bail!(
+                "Unsupported MQTT protocol_version '{}', expected \"v4\" or \"v5\"",
+                self.mqtt.protocol_version
+            );
+        }
+
+        if let Some(tls) = &self.mqtt.tls {
+            if !std::path::Path::new(&tls.ca_cert_path).exists() {
+                anyhow::bail!("TLS CA cert file does not exist: {}", tls.ca_cert_path);
+            }
+
+            match (&tls.client_cert_path, &tls.client_key_path) {
+                (Some(cert), Some(key)) => {
+                    if !std::path::Path::new(cert).exists() {
+                        anyhow::bail!("TLS client cert file does not exist: {}", cert);
+                    }
+                    if !std::path::Path::new(key).exists() {
+                        anyhow::bail!("TLS client key file does not exist: {}", key);
+                    }
+                }
+                (None, None) => {}
+                _ => anyhow::bail!(
+                    "TLS client_cert_path and client_key_path must be set together for mutual TLS"
+                ),
+            }
+        }
+
         if !std::path::Path::new(&self.wasm.module_path).exists() {
             anyhow::bail!("WASM module file does not exist: {}", self.wasm.module_path);
         }
@@ -121,21 +311,56 @@ impl AppConfig {
             anyhow::bail!("WASM max memory must be greater than 0");
         }
 
+        for source in &self.sources {
+            if let SourceConfig::Modbus(modbus) = source {
+                if modbus.registers.is_empty() {
+                    anyhow::bail!("Modbus source '{}' has no registers configured", modbus.topic);
+                }
+            }
+        }
+
+        if let Some(influx) = &self.influx {
+            if influx.host.is_empty() {
+                anyhow::bail!("InfluxDB host cannot be empty");
+            }
+            if influx.database.is_empty() {
+                anyhow::bail!("InfluxDB database cannot be empty");
+            }
+            if influx.export_interval_seconds == 0 {
+                anyhow::bail!("InfluxDB export_interval_seconds must be greater than 0");
+            }
+        }
+
+        if self.prometheus_port == 8080 {
+            anyhow::bail!("prometheus_port must differ from the fixed health/metrics HTTP port 8080");
+        }
+
         Ok(())
     }
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
+        let client_id = format!("wasm-quality-filter-{}", uuid::Uuid::new_v4());
+        let status_topic = format!("{}/status", client_id);
+        let health_check_topic = format!("azure-iot-operations/health-check/{}", client_id);
+        let health_status_topic = format!("azure-iot-operations/health-status/{}", client_id);
+
         Self {
             mqtt: MqttConfig {
                 broker_host: "aio-broker.azure-iot-operations.svc.cluster.local".to_string(),
                 broker_port: 1883,
-                client_id: format!("wasm-quality-filter-{}", uuid::Uuid::new_v4()),
+                client_id,
                 input_topic: "azure-iot-operations/data/welding-stations".to_string(),
                 output_topic: "azure-iot-operations/alerts/quality-control".to_string(),
                 qos: 1,
                 keep_alive: 60,
+                protocol_version: "v4".to_string(),
+                message_expiry_seconds: None,
+                status_topic,
+                health_check_topic,
+                health_status_topic,
+                tls: None,
             },
             wasm: WasmConfig {
                 module_path: "wasm_quality_filter.wasm".to_string(),
@@ -146,6 +371,9 @@ impl Default for AppConfig {
                 check_interval_seconds: 30,
                 unhealthy_threshold: 3,
             },
+            sources: Vec::new(),
+            influx: None,
+            prometheus_port: default_prometheus_port(),
         }
     }
 }
\ No newline at end of file
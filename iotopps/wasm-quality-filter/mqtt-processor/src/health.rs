@@ -30,6 +30,7 @@ pub struct CheckResult {
     pub last_checked: String,
 }
 
+#[derive(Clone)]
 pub struct HealthService {
     start_time: Instant,
     metrics: Arc<MetricsCollector>,
@@ -110,8 +111,18 @@ impl HealthService {
         // Check if we've had recent MQTT activity (no connection errors in last minute)
         let recent_errors = metrics_data.connection_errors;
         let recent_messages = metrics_data.messages_received;
+        let consecutive_failures = metrics_data.consecutive_connection_failures;
 
-        if recent_errors == 0 || recent_messages > 0 {
+        if consecutive_failures > 0 {
+            CheckResult {
+                status: "reconnecting".to_string(),
+                message: format!(
+                    "MQTT event loop is backing off and retrying, consecutive failures: {}",
+                    consecutive_failures
+                ),
+                last_checked: now.to_rfc3339(),
+            }
+        } else if recent_errors == 0 || recent_messages > 0 {
             CheckResult {
                 status: "healthy".to_string(),
                 message: format!(
@@ -207,7 +218,7 @@ impl HealthService {
 
     fn is_healthy(&self, checks: &HealthChecks) -> bool {
         matches!(checks.wasm_module.status.as_str(), "healthy")
-            && matches!(checks.mqtt_connection.status.as_str(), "healthy" | "degraded")
+            && matches!(checks.mqtt_connection.status.as_str(), "healthy" | "degraded" | "reconnecting")
             && matches!(checks.memory_usage.status.as_str(), "healthy" | "warning")
             && matches!(checks.message_processing.status.as_str(), "healthy" | "warning")
     }
@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+use tokio::time::{self, Duration};
+
+use crate::config::{ModbusRegister, ModbusRegisterType, ModbusSourceConfig};
+use crate::IncomingMessage;
+
+/// A source of raw `(topic, payload)` messages feeding the processing
+/// pipeline, independent of whether the bytes arrived over MQTT or were
+/// polled from a device that doesn't speak MQTT at all.
+#[async_trait]
+pub trait InputSource: Send {
+    /// Block until the next message is available.
+    async fn next_message(&mut self) -> Result<(String, String)>;
+}
+
+/// Adapts the channel fed by the MQTT event-loop task into an `InputSource`,
+/// so the MQTT subscriber is just one implementation among several rather
+/// than the pipeline's only possible input.
+pub struct MqttInputSource {
+    rx: mpsc::Receiver<IncomingMessage>,
+}
+
+impl MqttInputSource {
+    pub fn new(rx: mpsc::Receiver<IncomingMessage>) -> Self {
+        Self { rx }
+    }
+}
+
+#[async_trait]
+impl InputSource for MqttInputSource {
+    async fn next_message(&mut self) -> Result<(String, String)> {
+        match self.rx.recv().await {
+            Some(message) => Ok((message.topic, message.payload)),
+            None => anyhow::bail!("MQTT message channel closed"),
+        }
+    }
+}
+
+/// Polls holding/input registers on a Modbus TCP device at a fixed interval
+/// and emits them as a single JSON object keyed by register name, letting
+/// the WASM quality filter run directly against industrial devices that
+/// don't speak MQTT.
+pub struct ModbusSource {
+    topic: String,
+    config: ModbusSourceConfig,
+    ctx: tokio_modbus::client::Context,
+}
+
+impl ModbusSource {
+    pub async fn connect(config: ModbusSourceConfig) -> Result<Self> {
+        let socket_addr = format!("{}:{}", config.host, config.port)
+            .parse()
+            .with_context(|| format!("Invalid Modbus address {}:{}", config.host, config.port))?;
+
+        let ctx = tokio_modbus::client::tcp::connect_slave(socket_addr, tokio_modbus::Slave(config.unit_id))
+            .await
+            .context("Failed to connect to Modbus TCP device")?;
+
+        Ok(Self {
+            topic: config.topic.clone(),
+            config,
+            ctx,
+        })
+    }
+
+    async fn read_register(&mut self, register: &ModbusRegister) -> Result<f64> {
+        use tokio_modbus::prelude::*;
+
+        let raw = match register.register_type {
+            ModbusRegisterType::Holding => self
+                .ctx
+                .read_holding_registers(register.address, 1)
+                .await
+                .context("Modbus read_holding_registers failed")??,
+            ModbusRegisterType::Input => self
+                .ctx
+                .read_input_registers(register.address, 1)
+                .await
+                .context("Modbus read_input_registers failed")??,
+        };
+
+        let value = raw.first().copied().unwrap_or(0) as f64;
+        Ok(value * register.scale + register.offset)
+    }
+}
+
+#[async_trait]
+impl InputSource for ModbusSource {
+    async fn next_message(&mut self) -> Result<(String, String)> {
+        time::sleep(Duration::from_millis(self.config.poll_interval_ms)).await;
+
+        let mut fields = serde_json::Map::new();
+        for register in self.config.registers.clone() {
+            let value = self.read_register(&register).await?;
+            fields.insert(register.name.clone(), serde_json::json!(value));
+        }
+
+        let payload = serde_json::Value::Object(fields).to_string();
+        Ok((self.topic.clone(), payload))
+    }
+}
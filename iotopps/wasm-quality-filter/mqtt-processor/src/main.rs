@@ -1,137 +1,647 @@
 use anyhow::{Context, Result};
-use rumqttc::{AsyncClient, MqttOptions, QoS};
+use rand::Rng;
+use rumqttc::{AsyncClient, LastWill, MqttOptions, QoS};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 use tracing::{error, info, warn};
 use warp::Filter;
 
+/// Retained payload published to `status_topic` while the processor is
+/// actively servicing the MQTT event loop.
+const STATUS_RUNNING: &str = r#"{"status":"running"}"#;
+/// Retained payload published to `status_topic` on graceful shutdown, and set
+/// as the connection's Last Will so a crash or dropped connection reports the
+/// same thing.
+const STATUS_STOPPED: &str = r#"{"status":"stopped"}"#;
+
+/// Wraps whichever protocol-version client is in use so `main` can publish a
+/// final "stopped" status on graceful shutdown without caring which MQTT
+/// version is active.
+enum StatusPublisher {
+    V4(AsyncClient),
+    V5(rumqttc::v5::AsyncClient),
+}
+
+impl StatusPublisher {
+    async fn publish_stopped(&self, status_topic: &str) -> Result<()> {
+        match self {
+            StatusPublisher::V4(client) => {
+                client
+                    .publish(status_topic, QoS::AtLeastOnce, true, STATUS_STOPPED)
+                    .await
+                    .context("Failed to publish stopped status")?;
+            }
+            StatusPublisher::V5(client) => {
+                client
+                    .publish(status_topic, rumqttc::v5::mqttbytes::QoS::AtLeastOnce, true, STATUS_STOPPED)
+                    .await
+                    .context("Failed to publish stopped status")?;
+            }
+        }
+        Ok(())
+    }
+}
+
 mod config;
 mod wasm_runtime;
 mod health;
+mod input_source;
 mod metrics;
+mod metrics_exporter;
 
-use config::AppConfig;
+use config::{AppConfig, SourceConfig, TlsConfig};
 use wasm_runtime::WasmQualityFilter;
 use health::HealthService;
+use input_source::{InputSource, ModbusSource, MqttInputSource};
 use metrics::MetricsCollector;
+use metrics_exporter::MetricsExporter;
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .init();
+/// A message received from the broker, enriched with whatever protocol-level
+/// metadata the transport makes available. MQTT v3.1.1 carries none of this,
+/// so the v4 pipeline always produces empty/`None` values here.
+struct IncomingMessage {
+    topic: String,
+    payload: String,
+    user_properties: Vec<(String, String)>,
+    content_type: Option<String>,
+}
 
-    info!("🚀 Starting WASM Quality Filter MQTT Processor");
+/// Whether a v5 `Publish`'s declared content-type is JSON (or unset, since
+/// most brokers/publishers never set it). The WASM filter only understands
+/// JSON payloads, so anything else declared explicitly is skipped rather
+/// than fed in and logged as a parse error.
+fn is_json_content_type(content_type: &str) -> bool {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .eq_ignore_ascii_case("application/json")
+}
 
-    // Load configuration
-    let config = AppConfig::load().context("Failed to load configuration")?;
-    info!("📋 Configuration loaded successfully");
-    info!("📡 MQTT Broker: {}", config.mqtt.broker_host);
-    info!("📨 Input Topic: {}", config.mqtt.input_topic);
-    info!("📤 Output Topic: {}", config.mqtt.output_topic);
+/// Pull `station_id`/`severity` out of a serialized quality alert so they can
+/// ride along as MQTT v5 user properties, letting subscribers route/filter
+/// without parsing the JSON body themselves.
+fn alert_user_properties(alert_json: &str) -> Vec<(String, String)> {
+    let mut properties = Vec::new();
 
-    // Initialize WASM runtime
-    let wasm_filter = WasmQualityFilter::new(&config.wasm.module_path)
-        .context("Failed to initialize WASM runtime")?;
-    let wasm_filter = Arc::new(wasm_filter);
-    info!("🧠 WASM Quality Filter module loaded");
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(alert_json) else {
+        return properties;
+    };
 
-    // Initialize metrics collector
-    let metrics = Arc::new(MetricsCollector::new());
+    if let Some(station_id) = value
+        .pointer("/assembly_details/station_id")
+        .and_then(|v| v.as_str())
+    {
+        properties.push(("station_id".to_string(), station_id.to_string()));
+    }
 
-    // Initialize health service
-    let health_service = HealthService::new(metrics.clone());
+    if let Some(severity) = value.get("severity").and_then(|v| v.as_str()) {
+        properties.push(("severity".to_string(), severity.to_string()));
+    }
+
+    properties
+}
+
+/// Build a `rumqttc::Transport::Tls` from the configured CA/client cert
+/// paths, loading the PEM files from disk.
+fn build_tls_transport(tls: &TlsConfig) -> Result<rumqttc::Transport> {
+    let ca = std::fs::read(&tls.ca_cert_path)
+        .with_context(|| format!("Failed to read TLS CA cert: {}", tls.ca_cert_path))?;
+
+    let client_auth = match (&tls.client_cert_path, &tls.client_key_path) {
+        (Some(cert_path), Some(key_path)) => {
+            let cert = std::fs::read(cert_path)
+                .with_context(|| format!("Failed to read TLS client cert: {}", cert_path))?;
+            let key = std::fs::read(key_path)
+                .with_context(|| format!("Failed to read TLS client key: {}", key_path))?;
+            Some((cert, key))
+        }
+        _ => None,
+    };
+
+    Ok(rumqttc::Transport::Tls(rumqttc::TlsConfiguration::Simple {
+        ca,
+        alpn: None,
+        client_auth,
+    }))
+}
+
+/// Build a `rumqttc::v5::Transport::Tls` from the configured CA/client cert
+/// paths, loading the PEM files from disk.
+fn build_tls_transport_v5(tls: &TlsConfig) -> Result<rumqttc::v5::Transport> {
+    let ca = std::fs::read(&tls.ca_cert_path)
+        .with_context(|| format!("Failed to read TLS CA cert: {}", tls.ca_cert_path))?;
+
+    let client_auth = match (&tls.client_cert_path, &tls.client_key_path) {
+        (Some(cert_path), Some(key_path)) => {
+            let cert = std::fs::read(cert_path)
+                .with_context(|| format!("Failed to read TLS client cert: {}", cert_path))?;
+            let key = std::fs::read(key_path)
+                .with_context(|| format!("Failed to read TLS client key: {}", key_path))?;
+            Some((cert, key))
+        }
+        _ => None,
+    };
+
+    Ok(rumqttc::v5::Transport::Tls(rumqttc::v5::TlsConfiguration::Simple {
+        ca,
+        alpn: None,
+        client_auth,
+    }))
+}
+
+/// Exponential backoff with jitter for MQTT reconnect attempts: 1s, 2s, 4s,
+/// ... capped at 60s, plus up to 500ms of jitter to avoid a reconnect storm
+/// across many processors bouncing at once.
+fn reconnect_backoff(consecutive_failures: u64) -> Duration {
+    let exponent = consecutive_failures.saturating_sub(1).min(6);
+    let base_secs = (1u64 << exponent).min(60);
+    let jitter_ms = rand::thread_rng().gen_range(0..500);
+    Duration::from_secs(base_secs) + Duration::from_millis(jitter_ms)
+}
+
+/// Drive any `InputSource` to completion, converting each `(topic, payload)`
+/// it yields into an `IncomingMessage` and forwarding it into the shared
+/// processing channel. Neither Modbus nor a plain MQTT subscription carry
+/// MQTT v5 user-properties/content-type, so both are wrapped with empty
+/// metadata here; `spawn_v5_pipeline` builds `IncomingMessage`s directly
+/// instead, since it has that richer metadata to preserve.
+///
+/// `exit_on_error` controls whether a `next_message` error ends the task:
+/// `MqttInputSource`'s only error is its upstream channel closing for good,
+/// which will never un-close, so retrying it forever would just spin the
+/// task as fast as the executor can schedule it - pass `true` there. A
+/// single Modbus register read failing is more likely transient, so Modbus
+/// sources pass `false` and keep polling.
+async fn drive_input_source(
+    mut source: impl InputSource,
+    label: String,
+    tx: mpsc::Sender<IncomingMessage>,
+    exit_on_error: bool,
+) {
+    loop {
+        match source.next_message().await {
+            Ok((topic, payload)) => {
+                let message = IncomingMessage {
+                    topic,
+                    payload,
+                    user_properties: Vec::new(),
+                    content_type: None,
+                };
+                if let Err(e) = tx.send(message).await {
+                    error!("Failed to send {} message to processing queue: {}", label, e);
+                    return;
+                }
+            }
+            Err(e) => {
+                error!("{} poll failed: {}", label, e);
+                if exit_on_error {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Spawn one background polling task per configured `SourceConfig::Modbus`
+/// entry, forwarding each poll into `tx` so Modbus-sourced messages flow
+/// through the same processing task that MQTT-sourced ones do.
+fn spawn_modbus_sources(config: &AppConfig, tx: mpsc::Sender<IncomingMessage>) {
+    for source in &config.sources {
+        let SourceConfig::Modbus(modbus_config) = source else {
+            continue;
+        };
+
+        let modbus_config = modbus_config.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let topic = modbus_config.topic.clone();
+            let source = match ModbusSource::connect(modbus_config).await {
+                Ok(source) => source,
+                Err(e) => {
+                    error!("Failed to connect to Modbus source '{}': {}", topic, e);
+                    return;
+                }
+            };
+
+            info!("🔌 Polling Modbus source into topic: {}", topic);
+            drive_input_source(source, format!("Modbus('{}')", topic), tx, false).await;
+        });
+    }
+}
+
+/// Subscribe to `input_topic` and drive the processing pipeline over a plain
+/// MQTT v3.1.1 connection.
+async fn spawn_v4_pipeline(
+    config: &AppConfig,
+    wasm_filter: Arc<WasmQualityFilter>,
+    metrics: Arc<MetricsCollector>,
+    health_service: HealthService,
+) -> Result<(JoinHandle<()>, JoinHandle<()>, StatusPublisher)> {
+    let mut mqtt_options = MqttOptions::new(&config.mqtt.client_id, &config.mqtt.broker_host, config.mqtt.broker_port);
+    mqtt_options.set_last_will(LastWill::new(
+        &config.mqtt.status_topic,
+        STATUS_STOPPED,
+        QoS::AtLeastOnce,
+        true,
+    ));
+
+    if let Some(tls) = &config.mqtt.tls {
+        mqtt_options.set_transport(build_tls_transport(tls)?);
+    }
 
-    // Setup MQTT client
-    let mqtt_options = MqttOptions::new(&config.mqtt.client_id, &config.mqtt.broker_host, config.mqtt.broker_port);
     let (mqtt_client, mut mqtt_eventloop) = AsyncClient::new(mqtt_options, 10);
-    
-    // Subscribe to input topic
+
     mqtt_client
         .subscribe(&config.mqtt.input_topic, QoS::AtLeastOnce)
         .await
         .context("Failed to subscribe to input topic")?;
-    
-    info!("📡 Subscribed to topic: {}", config.mqtt.input_topic);
 
-    // Create message processing channel
-    let (tx, mut rx) = mpsc::channel::<(String, String)>(100);
+    mqtt_client
+        .subscribe(&config.mqtt.health_check_topic, QoS::AtLeastOnce)
+        .await
+        .context("Failed to subscribe to health check topic")?;
+
+    info!("📡 Subscribed to topic: {} (MQTT v3.1.1)", config.mqtt.input_topic);
+    info!("🏥 Subscribed to health check topic: {}", config.mqtt.health_check_topic);
 
-    // Clone references for tasks
-    let mqtt_client_clone = mqtt_client.clone();
-    let wasm_filter_clone = wasm_filter.clone();
-    let metrics_clone = metrics.clone();
+    mqtt_client
+        .publish(&config.mqtt.status_topic, QoS::AtLeastOnce, true, STATUS_RUNNING)
+        .await
+        .context("Failed to publish running status")?;
+
+    let (tx, mut rx) = mpsc::channel::<IncomingMessage>(100);
     let output_topic = config.mqtt.output_topic.clone();
+    let health_check_topic = config.mqtt.health_check_topic.clone();
+    let health_status_topic = config.mqtt.health_status_topic.clone();
+
+    spawn_modbus_sources(config, tx.clone());
+
+    // The event loop below hands raw publishes to `MqttInputSource` over
+    // `mqtt_raw_tx` rather than pushing onto `tx` directly, so the MQTT
+    // subscription is genuinely one `InputSource` implementation among
+    // several instead of the pipeline's only possible input.
+    let (mqtt_raw_tx, mqtt_raw_rx) = mpsc::channel::<IncomingMessage>(100);
+    tokio::spawn(drive_input_source(
+        MqttInputSource::new(mqtt_raw_rx),
+        format!("MQTT('{}')", config.mqtt.input_topic),
+        tx.clone(),
+        true,
+    ));
 
-    // Start MQTT event loop task
     let mqtt_task = {
-        let tx = tx.clone();
+        let tx = mqtt_raw_tx;
         let metrics = metrics.clone();
+        let client_handle = mqtt_client.clone();
+        let input_topic = config.mqtt.input_topic.clone();
         tokio::spawn(async move {
-            info!("🔄 Starting MQTT event loop");
+            info!("🔄 Starting MQTT v4 event loop");
             loop {
                 match mqtt_eventloop.poll().await {
+                    Ok(rumqttc::Event::Incoming(rumqttc::Packet::ConnAck(connack))) => {
+                        metrics.record_reconnect();
+                        if !connack.session_present {
+                            info!("🔁 Connected with a clean session, re-subscribing to {}", input_topic);
+                            if let Err(e) = client_handle.subscribe(&input_topic, QoS::AtLeastOnce).await {
+                                error!("Failed to re-subscribe to input topic: {}", e);
+                            }
+                            if let Err(e) = client_handle.subscribe(&health_check_topic, QoS::AtLeastOnce).await {
+                                error!("Failed to re-subscribe to health check topic: {}", e);
+                            }
+                        }
+                    }
+                    Ok(rumqttc::Event::Incoming(rumqttc::Packet::Publish(publish))) if publish.topic == health_check_topic => {
+                        info!("🏥 Health check requested over MQTT");
+                        match health_service.check_health().await {
+                            Ok(status) => {
+                                if let Ok(payload) = serde_json::to_string(&status) {
+                                    if let Err(e) = client_handle
+                                        .publish(&health_status_topic, QoS::AtLeastOnce, false, payload)
+                                        .await
+                                    {
+                                        error!("Failed to publish health status: {}", e);
+                                    }
+                                }
+                            }
+                            Err(e) => error!("Failed to compute health status: {}", e),
+                        }
+                    }
                     Ok(rumqttc::Event::Incoming(rumqttc::Packet::Publish(publish))) => {
                         let topic = publish.topic.clone();
                         let payload = String::from_utf8_lossy(&publish.payload).to_string();
-                        
+
+                        metrics.increment_messages_received();
+
+                        let message = IncomingMessage {
+                            topic,
+                            payload,
+                            user_properties: Vec::new(),
+                            content_type: None,
+                        };
+
+                        if let Err(e) = tx.send(message).await {
+                            error!("Failed to send message to processing queue: {}", e);
+                        }
+                    }
+                    Ok(_) => {} // Other MQTT events
+                    Err(e) => {
+                        let consecutive_failures = metrics.record_connection_failure();
+                        let backoff = reconnect_backoff(consecutive_failures);
+                        warn!(
+                            "MQTT connection error (attempt {}): {}. Retrying in {:?}",
+                            consecutive_failures, e, backoff
+                        );
+                        tokio::time::sleep(backoff).await;
+                    }
+                }
+            }
+        })
+    };
+
+    let processing_task = {
+        let metrics = metrics.clone();
+        let mqtt_client = mqtt_client.clone();
+        tokio::spawn(async move {
+            info!("⚙️ Starting message processing task");
+            while let Some(message) = rx.recv().await {
+                let start_time = std::time::Instant::now();
+
+                match wasm_filter.process_message(&message.payload).await {
+                    Ok(Some(alert)) => {
+                        info!("🚨 Quality alert generated for topic: {}", message.topic);
+
+                        if let Err(e) = mqtt_client
+                            .publish(&output_topic, QoS::AtLeastOnce, false, alert)
+                            .await
+                        {
+                            error!("Failed to publish quality alert: {}", e);
+                            metrics.increment_publish_errors();
+                        } else {
+                            metrics.increment_alerts_generated();
+                            info!("✅ Quality alert published successfully");
+                        }
+                    }
+                    Ok(None) => {
+                        tracing::debug!("No quality alert needed for message from topic: {}", message.topic);
+                    }
+                    Err(e) => {
+                        error!("Failed to process message: {}", e);
+                        metrics.increment_processing_errors();
+                    }
+                }
+
+                let processing_duration = start_time.elapsed();
+                metrics.record_processing_latency(processing_duration);
+                metrics.increment_messages_processed();
+            }
+        })
+    };
+
+    Ok((mqtt_task, processing_task, StatusPublisher::V4(mqtt_client)))
+}
+
+/// Subscribe to `input_topic` and drive the processing pipeline over MQTT v5,
+/// surfacing per-message user properties/content-type and attaching
+/// `station_id`/`severity` plus a message-expiry interval to outgoing alerts.
+async fn spawn_v5_pipeline(
+    config: &AppConfig,
+    wasm_filter: Arc<WasmQualityFilter>,
+    metrics: Arc<MetricsCollector>,
+    health_service: HealthService,
+) -> Result<(JoinHandle<()>, JoinHandle<()>, StatusPublisher)> {
+    use rumqttc::v5::mqttbytes::v5::{LastWill as LastWillV5, Packet as PacketV5, PublishProperties};
+    use rumqttc::v5::mqttbytes::QoS as QoSV5;
+    use rumqttc::v5::{AsyncClient as AsyncClientV5, Event as EventV5, MqttOptions as MqttOptionsV5};
+
+    let mut mqtt_options = MqttOptionsV5::new(&config.mqtt.client_id, &config.mqtt.broker_host, config.mqtt.broker_port);
+    mqtt_options.set_last_will(LastWillV5::new(
+        &config.mqtt.status_topic,
+        STATUS_STOPPED,
+        QoSV5::AtLeastOnce,
+        true,
+        None,
+    ));
+
+    if let Some(tls) = &config.mqtt.tls {
+        mqtt_options.set_transport(build_tls_transport_v5(tls)?);
+    }
+
+    let (mqtt_client, mut mqtt_eventloop) = AsyncClientV5::new(mqtt_options, 10);
+
+    mqtt_client
+        .subscribe(&config.mqtt.input_topic, QoSV5::AtLeastOnce)
+        .await
+        .context("Failed to subscribe to input topic")?;
+
+    mqtt_client
+        .subscribe(&config.mqtt.health_check_topic, QoSV5::AtLeastOnce)
+        .await
+        .context("Failed to subscribe to health check topic")?;
+
+    info!("📡 Subscribed to topic: {} (MQTT v5)", config.mqtt.input_topic);
+    info!("🏥 Subscribed to health check topic: {}", config.mqtt.health_check_topic);
+
+    mqtt_client
+        .publish(&config.mqtt.status_topic, QoSV5::AtLeastOnce, true, STATUS_RUNNING)
+        .await
+        .context("Failed to publish running status")?;
+
+    let (tx, mut rx) = mpsc::channel::<IncomingMessage>(100);
+    let output_topic = config.mqtt.output_topic.clone();
+    let message_expiry_seconds = config.mqtt.message_expiry_seconds;
+    let health_check_topic = config.mqtt.health_check_topic.clone();
+    let health_status_topic = config.mqtt.health_status_topic.clone();
+
+    spawn_modbus_sources(config, tx.clone());
+
+    let mqtt_task = {
+        let tx = tx.clone();
+        let metrics = metrics.clone();
+        let client_handle = mqtt_client.clone();
+        let input_topic = config.mqtt.input_topic.clone();
+        tokio::spawn(async move {
+            info!("🔄 Starting MQTT v5 event loop");
+            loop {
+                match mqtt_eventloop.poll().await {
+                    Ok(EventV5::Incoming(PacketV5::ConnAck(connack))) => {
+                        metrics.record_reconnect();
+                        if !connack.session_present {
+                            info!("🔁 Connected with a clean session, re-subscribing to {}", input_topic);
+                            if let Err(e) = client_handle.subscribe(&input_topic, QoSV5::AtLeastOnce).await {
+                                error!("Failed to re-subscribe to input topic: {}", e);
+                            }
+                            if let Err(e) = client_handle.subscribe(&health_check_topic, QoSV5::AtLeastOnce).await {
+                                error!("Failed to re-subscribe to health check topic: {}", e);
+                            }
+                        }
+                    }
+                    Ok(EventV5::Incoming(PacketV5::Publish(publish))) if publish.topic == health_check_topic => {
+                        info!("🏥 Health check requested over MQTT");
+                        match health_service.check_health().await {
+                            Ok(status) => {
+                                if let Ok(payload) = serde_json::to_string(&status) {
+                                    if let Err(e) = client_handle
+                                        .publish(&health_status_topic, QoSV5::AtLeastOnce, false, payload)
+                                        .await
+                                    {
+                                        error!("Failed to publish health status: {}", e);
+                                    }
+                                }
+                            }
+                            Err(e) => error!("Failed to compute health status: {}", e),
+                        }
+                    }
+                    Ok(EventV5::Incoming(PacketV5::Publish(publish))) => {
+                        let topic = publish.topic.clone();
+                        let payload = String::from_utf8_lossy(&publish.payload).to_string();
+
                         metrics.increment_messages_received();
-                        
-                        if let Err(e) = tx.send((topic, payload)).await {
+
+                        let (user_properties, content_type) = publish
+                            .properties
+                            .map(|p| (p.user_properties, p.content_type))
+                            .unwrap_or_default();
+
+                        let message = IncomingMessage {
+                            topic,
+                            payload,
+                            user_properties,
+                            content_type,
+                        };
+
+                        if let Err(e) = tx.send(message).await {
                             error!("Failed to send message to processing queue: {}", e);
                         }
                     }
                     Ok(_) => {} // Other MQTT events
                     Err(e) => {
-                        error!("MQTT connection error: {}", e);
-                        metrics.increment_connection_errors();
-                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        let consecutive_failures = metrics.record_connection_failure();
+                        let backoff = reconnect_backoff(consecutive_failures);
+                        warn!(
+                            "MQTT connection error (attempt {}): {}. Retrying in {:?}",
+                            consecutive_failures, e, backoff
+                        );
+                        tokio::time::sleep(backoff).await;
                     }
                 }
             }
         })
     };
 
-    // Start message processing task
-    let processing_task = tokio::spawn(async move {
-        info!("⚙️ Starting message processing task");
-        while let Some((topic, payload)) = rx.recv().await {
-            let start_time = std::time::Instant::now();
-            
-            match wasm_filter_clone.process_message(&payload).await {
-                Ok(Some(alert)) => {
-                    // Quality control alert generated
-                    info!("🚨 Quality alert generated for topic: {}", topic);
-                    
-                    // Publish alert to output topic
-                    if let Err(e) = mqtt_client_clone
-                        .publish(&output_topic, QoS::AtLeastOnce, false, alert)
-                        .await
-                    {
-                        error!("Failed to publish quality alert: {}", e);
-                        metrics_clone.increment_publish_errors();
-                    } else {
-                        metrics_clone.increment_alerts_generated();
-                        info!("✅ Quality alert published successfully");
+    let processing_task = {
+        let metrics = metrics.clone();
+        let mqtt_client = mqtt_client.clone();
+        tokio::spawn(async move {
+            info!("⚙️ Starting message processing task");
+            while let Some(message) = rx.recv().await {
+                let start_time = std::time::Instant::now();
+
+                if let Some(content_type) = &message.content_type {
+                    if !is_json_content_type(content_type) {
+                        tracing::warn!(
+                            "Skipping message from topic {} with non-JSON content-type: {}",
+                            message.topic, content_type
+                        );
+                        continue;
                     }
                 }
-                Ok(None) => {
-                    // No alert needed for this message
-                    tracing::debug!("No quality alert needed for message from topic: {}", topic);
+                if !message.user_properties.is_empty() {
+                    tracing::debug!("Incoming publish user properties: {:?}", message.user_properties);
                 }
-                Err(e) => {
-                    error!("Failed to process message: {}", e);
-                    metrics_clone.increment_processing_errors();
+
+                match wasm_filter.process_message(&message.payload).await {
+                    Ok(Some(alert)) => {
+                        info!("🚨 Quality alert generated for topic: {}", message.topic);
+
+                        // Carry the producer's own user properties (e.g. a
+                        // correlation id) through to the alert, alongside the
+                        // ones derived from the alert itself.
+                        let mut user_properties = message.user_properties.clone();
+                        user_properties.extend(alert_user_properties(&alert));
+
+                        let properties = PublishProperties {
+                            user_properties,
+                            message_expiry_interval: message_expiry_seconds,
+                            content_type: Some("application/json".to_string()),
+                            ..Default::default()
+                        };
+
+                        if let Err(e) = mqtt_client
+                            .publish_with_properties(&output_topic, QoSV5::AtLeastOnce, false, alert, properties)
+                            .await
+                        {
+                            error!("Failed to publish quality alert: {}", e);
+                            metrics.increment_publish_errors();
+                        } else {
+                            metrics.increment_alerts_generated();
+                            info!("✅ Quality alert published successfully");
+                        }
+                    }
+                    Ok(None) => {
+                        tracing::debug!("No quality alert needed for message from topic: {}", message.topic);
+                    }
+                    Err(e) => {
+                        error!("Failed to process message: {}", e);
+                        metrics.increment_processing_errors();
+                    }
                 }
+
+                let processing_duration = start_time.elapsed();
+                metrics.record_processing_latency(processing_duration);
+                metrics.increment_messages_processed();
             }
-            
-            let processing_duration = start_time.elapsed();
-            metrics_clone.record_processing_latency(processing_duration);
-            metrics_clone.increment_messages_processed();
-        }
-    });
+        })
+    };
+
+    Ok((mqtt_task, processing_task, StatusPublisher::V5(mqtt_client)))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize logging
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    info!("🚀 Starting WASM Quality Filter MQTT Processor");
+
+    // Load configuration
+    let config = AppConfig::load().context("Failed to load configuration")?;
+    config.validate().context("Invalid configuration")?;
+    info!("📋 Configuration loaded successfully");
+    info!("📡 MQTT Broker: {}", config.mqtt.broker_host);
+    info!("🔌 MQTT Protocol: {}", config.mqtt.protocol_version);
+    info!("📨 Input Topic: {}", config.mqtt.input_topic);
+    info!("📤 Output Topic: {}", config.mqtt.output_topic);
+
+    // Initialize WASM runtime
+    let wasm_filter = WasmQualityFilter::new(&config.wasm.module_path)
+        .context("Failed to initialize WASM runtime")?;
+    let wasm_filter = Arc::new(wasm_filter);
+    info!("🧠 WASM Quality Filter module loaded");
+
+    // Initialize metrics collector
+    let metrics = Arc::new(MetricsCollector::new());
+
+    // Initialize health service
+    let health_service = HealthService::new(metrics.clone());
+
+    // Start the InfluxDB metrics exporter, if configured
+    if let Some(influx_config) = &config.influx {
+        let exporter = Arc::new(MetricsExporter::new(influx_config.clone(), metrics.clone()));
+        exporter.spawn();
+        info!("📈 Exporting metrics to InfluxDB at {}", influx_config.host);
+    }
+
+    // Start the MQTT pipeline on whichever protocol version is configured
+    let (mqtt_task, processing_task, status_publisher) = match config.mqtt.protocol_version.as_str() {
+        "v5" => spawn_v5_pipeline(&config, wasm_filter.clone(), metrics.clone(), health_service.clone()).await?,
+        _ => spawn_v4_pipeline(&config, wasm_filter.clone(), metrics.clone(), health_service.clone()).await?,
+    };
+    info!("💡 Published running status to {}", config.mqtt.status_topic);
 
     // Start health check HTTP server
     let health_routes = warp::path("health")
@@ -163,13 +673,37 @@ async fn main() -> Result<()> {
         });
 
     let routes = health_routes.or(metrics_routes);
-    
+
     let http_server = warp::serve(routes).run(([0, 0, 0, 0], 8080));
 
     info!("🌐 Health check server started on http://0.0.0.0:8080");
     info!("💊 Health endpoint: http://0.0.0.0:8080/health");
     info!("📊 Metrics endpoint: http://0.0.0.0:8080/metrics");
 
+    // Pull-based Prometheus scrape endpoint, complementing the InfluxDB push
+    // path above. Served on its own configurable port rather than sharing
+    // port 8080, so it can sit at the conventional `/metrics` path instead of
+    // colliding with the JSON metrics route above. Only compiled in when the
+    // `prometheus` feature is enabled.
+    #[cfg(feature = "prometheus")]
+    let prometheus_server = {
+        let machine_id = config.mqtt.client_id.clone();
+        let prometheus_routes = warp::path("metrics").and(warp::get()).and_then({
+            let metrics = metrics.clone();
+            move || {
+                let metrics = metrics.clone();
+                let machine_id = machine_id.clone();
+                async move {
+                    let body = metrics.get_metrics().to_prometheus(&machine_id);
+                    Ok::<_, warp::Rejection>(body)
+                }
+            }
+        });
+
+        info!("📈 Prometheus endpoint: http://0.0.0.0:{}/metrics", config.prometheus_port);
+        warp::serve(prometheus_routes).run(([0, 0, 0, 0], config.prometheus_port))
+    };
+
     // Run all tasks concurrently
     tokio::select! {
         result = mqtt_task => {
@@ -181,11 +715,18 @@ async fn main() -> Result<()> {
         result = http_server => {
             error!("HTTP server ended unexpectedly: {:?}", result);
         }
+        #[cfg(feature = "prometheus")]
+        result = prometheus_server => {
+            error!("Prometheus server ended unexpectedly: {:?}", result);
+        }
         _ = tokio::signal::ctrl_c() => {
             info!("🛑 Received shutdown signal, gracefully shutting down...");
+            if let Err(e) = status_publisher.publish_stopped(&config.mqtt.status_topic).await {
+                error!("Failed to publish stopped status: {}", e);
+            }
         }
     }
 
     info!("👋 WASM Quality Filter MQTT Processor stopped");
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,8 +1,105 @@
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+
+/// Number of buckets in the latency histogram.
+const LATENCY_HISTOGRAM_BUCKETS: usize = 64;
+/// Lower bound (ms) of bucket 0.
+const LATENCY_HISTOGRAM_BASE_MS: f64 = 0.1;
+/// Geometric growth factor between consecutive bucket bounds.
+const LATENCY_HISTOGRAM_RATIO: f64 = 1.5;
+
+/// A fixed-memory, lock-free latency histogram: each bucket `i` covers
+/// `[base*ratio^i, base*ratio^(i+1))` milliseconds and tracks a count plus a
+/// running sum so percentiles can be read without cloning raw samples or
+/// blocking on a lock.
+struct LatencyHistogram {
+    counts: [AtomicU64; LATENCY_HISTOGRAM_BUCKETS],
+    sums_us: [AtomicU64; LATENCY_HISTOGRAM_BUCKETS],
+}
+
+impl LatencyHistogram {
+    fn new() -> Self {
+        Self {
+            counts: std::array::from_fn(|_| AtomicU64::new(0)),
+            sums_us: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+
+    fn bucket_lower_bound_ms(index: usize) -> f64 {
+        LATENCY_HISTOGRAM_BASE_MS * LATENCY_HISTOGRAM_RATIO.powi(index as i32)
+    }
+
+    fn bucket_for(value_ms: f64) -> usize {
+        if value_ms <= LATENCY_HISTOGRAM_BASE_MS {
+            return 0;
+        }
+        // The small epsilon keeps a value sitting exactly on a bucket's lower
+        // bound (e.g. 0.15ms) from rounding down into the previous bucket.
+        let index = ((value_ms / LATENCY_HISTOGRAM_BASE_MS).ln() / LATENCY_HISTOGRAM_RATIO.ln() + 1e-9).floor();
+        (index as usize).min(LATENCY_HISTOGRAM_BUCKETS - 1)
+    }
+
+    fn record(&self, latency: Duration) {
+        let value_ms = latency.as_secs_f64() * 1000.0;
+        let bucket = Self::bucket_for(value_ms);
+        self.counts[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sums_us[bucket].fetch_add((latency.as_micros() as u64).max(1), Ordering::Relaxed);
+    }
+
+    fn total_count(&self) -> u64 {
+        self.counts.iter().map(|c| c.load(Ordering::Relaxed)).sum()
+    }
+
+    fn max_ms(&self) -> u64 {
+        for index in (0..LATENCY_HISTOGRAM_BUCKETS).rev() {
+            if self.counts[index].load(Ordering::Relaxed) > 0 {
+                return Self::bucket_lower_bound_ms(index + 1) as u64;
+            }
+        }
+        0
+    }
+
+    fn avg_ms(&self) -> f64 {
+        let total_count = self.total_count();
+        if total_count == 0 {
+            return 0.0;
+        }
+        let total_us: u64 = self.sums_us.iter().map(|s| s.load(Ordering::Relaxed)).sum();
+        (total_us as f64 / 1000.0) / total_count as f64
+    }
+
+    /// Estimate the `q`th percentile (0.0-1.0) by walking buckets until the
+    /// target rank is reached, interpolating linearly within the straddling
+    /// bucket's `[lo, hi)` range.
+    fn percentile_ms(&self, q: f64) -> f64 {
+        let total_count = self.total_count();
+        if total_count == 0 {
+            return 0.0;
+        }
+
+        let target = ((q * total_count as f64).ceil() as u64).max(1);
+        let mut accumulated = 0u64;
+
+        for index in 0..LATENCY_HISTOGRAM_BUCKETS {
+            let count = self.counts[index].load(Ordering::Relaxed);
+            if count == 0 {
+                continue;
+            }
+
+            accumulated += count;
+            if accumulated >= target {
+                let lo = Self::bucket_lower_bound_ms(index);
+                let hi = Self::bucket_lower_bound_ms(index + 1);
+                let rank_within_bucket = count - (accumulated - target);
+                let fraction = rank_within_bucket as f64 / count as f64;
+                return lo + (hi - lo) * fraction;
+            }
+        }
+
+        Self::bucket_lower_bound_ms(LATENCY_HISTOGRAM_BUCKETS)
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsData {
@@ -14,9 +111,60 @@ pub struct MetricsData {
     pub publish_errors: u64,
     pub avg_processing_latency_ms: f64,
     pub max_processing_latency_ms: u64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
     pub uptime_seconds: u64,
     pub filter_hit_rate: f64,
     pub timestamp: String,
+    /// Number of successful MQTT `ConnAck`s, including the initial connect,
+    /// whether or not the broker kept the previous session.
+    pub reconnect_count: u64,
+    /// Consecutive MQTT connection failures since the last successful
+    /// `ConnAck`. Non-zero means the event loop is currently backing off
+    /// and retrying.
+    pub consecutive_connection_failures: u64,
+}
+
+#[cfg(feature = "prometheus")]
+impl MetricsData {
+    /// Render this snapshot in Prometheus exposition format, labeling every
+    /// sample with `machine="<machine_id>"` so a single scrape target can be
+    /// disambiguated once multiple processors feed the same Prometheus.
+    pub fn to_prometheus(&self, machine_id: &str) -> String {
+        let mut out = String::new();
+
+        let counter = |out: &mut String, name: &str, help: &str, value: u64| {
+            out.push_str(&format!("# HELP {name} {help}\n"));
+            out.push_str(&format!("# TYPE {name} counter\n"));
+            out.push_str(&format!("{name}{{machine=\"{machine_id}\"}} {value}\n"));
+        };
+
+        let gauge = |out: &mut String, name: &str, help: &str, value: f64| {
+            out.push_str(&format!("# HELP {name} {help}\n"));
+            out.push_str(&format!("# TYPE {name} gauge\n"));
+            out.push_str(&format!("{name}{{machine=\"{machine_id}\"}} {value}\n"));
+        };
+
+        counter(&mut out, "messages_received_total", "Total MQTT messages received", self.messages_received);
+        counter(&mut out, "messages_processed_total", "Total messages run through the WASM quality filter", self.messages_processed);
+        counter(&mut out, "alerts_generated_total", "Total quality alerts generated", self.alerts_generated);
+        counter(&mut out, "processing_errors_total", "Total WASM processing errors", self.processing_errors);
+        counter(&mut out, "connection_errors_total", "Total MQTT connection errors", self.connection_errors);
+        counter(&mut out, "publish_errors_total", "Total failed alert/export publishes", self.publish_errors);
+        counter(&mut out, "reconnect_total", "Total successful MQTT (re)connections", self.reconnect_count);
+
+        gauge(&mut out, "filter_hit_rate", "Percentage of processed messages that generated an alert", self.filter_hit_rate);
+        gauge(&mut out, "avg_processing_latency_ms", "Average WASM processing latency in milliseconds", self.avg_processing_latency_ms);
+        gauge(&mut out, "max_processing_latency_ms", "Approximate max WASM processing latency in milliseconds", self.max_processing_latency_ms as f64);
+        gauge(&mut out, "processing_latency_p50_ms", "50th percentile WASM processing latency in milliseconds", self.p50_ms);
+        gauge(&mut out, "processing_latency_p90_ms", "90th percentile WASM processing latency in milliseconds", self.p90_ms);
+        gauge(&mut out, "processing_latency_p99_ms", "99th percentile WASM processing latency in milliseconds", self.p99_ms);
+        gauge(&mut out, "uptime_seconds", "Seconds since the processor started", self.uptime_seconds as f64);
+        gauge(&mut out, "consecutive_connection_failures", "Current MQTT reconnect backoff streak", self.consecutive_connection_failures as f64);
+
+        out
+    }
 }
 
 pub struct MetricsCollector {
@@ -26,7 +174,9 @@ pub struct MetricsCollector {
     processing_errors: AtomicU64,
     connection_errors: AtomicU64,
     publish_errors: AtomicU64,
-    processing_latencies: Arc<RwLock<Vec<Duration>>>,
+    reconnect_count: AtomicU64,
+    consecutive_connection_failures: AtomicU64,
+    processing_latencies: LatencyHistogram,
     start_time: Instant,
 }
 
@@ -39,7 +189,9 @@ impl MetricsCollector {
             processing_errors: AtomicU64::new(0),
             connection_errors: AtomicU64::new(0),
             publish_errors: AtomicU64::new(0),
-            processing_latencies: Arc::new(RwLock::new(Vec::new())),
+            reconnect_count: AtomicU64::new(0),
+            consecutive_connection_failures: AtomicU64::new(0),
+            processing_latencies: LatencyHistogram::new(),
             start_time: Instant::now(),
         }
     }
@@ -60,27 +212,27 @@ impl MetricsCollector {
         self.processing_errors.fetch_add(1, Ordering::Relaxed);
     }
 
-    pub fn increment_connection_errors(&self) {
+    pub fn increment_publish_errors(&self) {
+        self.publish_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a failed MQTT connection attempt and return the new
+    /// consecutive-failure count, used to size the next backoff sleep.
+    pub fn record_connection_failure(&self) -> u64 {
         self.connection_errors.fetch_add(1, Ordering::Relaxed);
+        self.consecutive_connection_failures.fetch_add(1, Ordering::Relaxed) + 1
     }
 
-    pub fn increment_publish_errors(&self) {
-        self.publish_errors.fetch_add(1, Ordering::Relaxed);
+    /// Record a successful (re)connection: bumps `reconnect_count` and clears
+    /// the consecutive-failure streak so the health check stops reporting
+    /// "reconnecting".
+    pub fn record_reconnect(&self) {
+        self.reconnect_count.fetch_add(1, Ordering::Relaxed);
+        self.consecutive_connection_failures.store(0, Ordering::Relaxed);
     }
 
     pub fn record_processing_latency(&self, latency: Duration) {
-        tokio::spawn({
-            let latencies = self.processing_latencies.clone();
-            async move {
-                let mut latencies = latencies.write().await;
-                latencies.push(latency);
-                
-                // Keep only the last 1000 measurements to prevent unbounded growth
-                if latencies.len() > 1000 {
-                    latencies.drain(0..500); // Remove oldest 500
-                }
-            }
-        });
+        self.processing_latencies.record(latency);
     }
 
     pub fn get_metrics(&self) -> MetricsData {
@@ -99,24 +251,6 @@ impl MetricsCollector {
             0.0
         };
 
-        // Calculate latency statistics (this is a simplified version)
-        let (avg_latency_ms, max_latency_ms) = {
-            // For now, we'll use blocking to get latency stats
-            // In a production system, you might want to cache these values
-            let latencies = futures::executor::block_on(async {
-                self.processing_latencies.read().await.clone()
-            });
-
-            if latencies.is_empty() {
-                (0.0, 0)
-            } else {
-                let total_ms: u64 = latencies.iter().map(|d| d.as_millis() as u64).sum();
-                let avg_ms = total_ms as f64 / latencies.len() as f64;
-                let max_ms = latencies.iter().map(|d| d.as_millis() as u64).max().unwrap_or(0);
-                (avg_ms, max_ms)
-            }
-        };
-
         MetricsData {
             messages_received,
             messages_processed,
@@ -124,11 +258,16 @@ impl MetricsCollector {
             processing_errors,
             connection_errors,
             publish_errors,
-            avg_processing_latency_ms: avg_latency_ms,
-            max_processing_latency_ms: max_latency_ms,
+            avg_processing_latency_ms: self.processing_latencies.avg_ms(),
+            max_processing_latency_ms: self.processing_latencies.max_ms(),
+            p50_ms: self.processing_latencies.percentile_ms(0.50),
+            p90_ms: self.processing_latencies.percentile_ms(0.90),
+            p99_ms: self.processing_latencies.percentile_ms(0.99),
             uptime_seconds: uptime.as_secs(),
             filter_hit_rate,
             timestamp: chrono::Utc::now().to_rfc3339(),
+            reconnect_count: self.reconnect_count.load(Ordering::Relaxed),
+            consecutive_connection_failures: self.consecutive_connection_failures.load(Ordering::Relaxed),
         }
     }
 
@@ -140,14 +279,13 @@ impl MetricsCollector {
         self.processing_errors.store(0, Ordering::Relaxed);
         self.connection_errors.store(0, Ordering::Relaxed);
         self.publish_errors.store(0, Ordering::Relaxed);
-        
-        tokio::spawn({
-            let latencies = self.processing_latencies.clone();
-            async move {
-                let mut latencies = latencies.write().await;
-                latencies.clear();
-            }
-        });
+        self.reconnect_count.store(0, Ordering::Relaxed);
+        self.consecutive_connection_failures.store(0, Ordering::Relaxed);
+
+        for bucket in 0..LATENCY_HISTOGRAM_BUCKETS {
+            self.processing_latencies.counts[bucket].store(0, Ordering::Relaxed);
+            self.processing_latencies.sums_us[bucket].store(0, Ordering::Relaxed);
+        }
     }
 
     /// Get a summary string for logging
@@ -254,13 +392,58 @@ mod tests {
     #[test]
     fn test_get_summary() {
         let collector = MetricsCollector::new();
-        
+
         collector.increment_messages_processed();
         collector.increment_alerts_generated();
-        
+
         let summary = collector.get_summary();
         assert!(summary.contains("Processed: 1"));
         assert!(summary.contains("Alerts: 1"));
         assert!(summary.contains("Hit Rate: 100.0%"));
     }
+
+    #[test]
+    fn test_bucket_for_boundaries() {
+        // Below and at the base fall into bucket 0.
+        assert_eq!(LatencyHistogram::bucket_for(0.05), 0);
+        assert_eq!(LatencyHistogram::bucket_for(0.1), 0);
+        // Just under the bucket-1 lower bound (0.1 * 1.5) still rounds down to 0.
+        assert_eq!(LatencyHistogram::bucket_for(0.1499999), 0);
+        // At or past a bucket's lower bound, it belongs to that bucket.
+        assert_eq!(LatencyHistogram::bucket_for(0.15), 1);
+        assert_eq!(LatencyHistogram::bucket_for(0.225), 2);
+        // Anything past the last bucket's range clamps to the final index.
+        assert_eq!(LatencyHistogram::bucket_for(1e12), LATENCY_HISTOGRAM_BUCKETS - 1);
+    }
+
+    #[test]
+    fn test_percentile_ms_interpolates_within_straddling_bucket() {
+        let histogram = LatencyHistogram::new();
+        for _ in 0..5 {
+            histogram.record(Duration::from_millis(1));
+        }
+        for _ in 0..5 {
+            histogram.record(Duration::from_millis(2));
+        }
+
+        assert_eq!(histogram.total_count(), 10);
+        assert!((histogram.avg_ms() - 1.5).abs() < 1e-9);
+        assert_eq!(histogram.max_ms(), 2);
+
+        // 1ms samples land in bucket 5 ([0.759375, 1.1390625)), 2ms samples in
+        // bucket 7 ([1.70859375, 2.562890625)) - known bucket edges for
+        // base=0.1/ratio=1.5, checked against `test_bucket_for_boundaries`'s
+        // sibling cases.
+        assert!((histogram.percentile_ms(0.1) - 0.8353125).abs() < 1e-6);
+        assert!((histogram.percentile_ms(0.5) - 1.1390625).abs() < 1e-6);
+        assert!((histogram.percentile_ms(0.99) - 2.562890625).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_percentile_ms_empty_histogram_is_zero() {
+        let histogram = LatencyHistogram::new();
+        assert_eq!(histogram.percentile_ms(0.5), 0.0);
+        assert_eq!(histogram.avg_ms(), 0.0);
+        assert_eq!(histogram.max_ms(), 0);
+    }
 }
\ No newline at end of file
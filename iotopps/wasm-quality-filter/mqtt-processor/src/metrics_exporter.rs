@@ -0,0 +1,192 @@
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+
+use crate::config::InfluxConfig;
+use crate::metrics::{MetricsCollector, MetricsData};
+
+/// Periodically ships `MetricsCollector` snapshots to InfluxDB as line
+/// protocol, turning the otherwise log-only counters into a queryable
+/// time series for dashboards.
+pub struct MetricsExporter {
+    config: InfluxConfig,
+    metrics: Arc<MetricsCollector>,
+    client: reqwest::Client,
+    /// Line-protocol batches that failed to send, retried alongside the next
+    /// export instead of being dropped.
+    failed_batches: Mutex<Vec<String>>,
+}
+
+impl MetricsExporter {
+    pub fn new(config: InfluxConfig, metrics: Arc<MetricsCollector>) -> Self {
+        Self {
+            config,
+            metrics,
+            client: reqwest::Client::new(),
+            failed_batches: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Spawn the export loop as a detached background task.
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+                self.config.export_interval_seconds,
+            ));
+            loop {
+                interval.tick().await;
+                self.export_once().await;
+            }
+        })
+    }
+
+    async fn export_once(&self) {
+        let line = format_line(&self.metrics.get_metrics(), &self.config.machine_id);
+
+        let mut batches = self.failed_batches.lock().await;
+        batches.push(line);
+        let body = batches.join("\n");
+
+        match self.send_batch(&body).await {
+            Ok(()) => batches.clear(),
+            Err(e) => warn!(
+                "Failed to export metrics to InfluxDB, buffering {} batch(es) for retry: {}",
+                batches.len(),
+                e
+            ),
+        }
+    }
+
+    async fn send_batch(&self, body: &str) -> anyhow::Result<()> {
+        let url = format!(
+            "http://{}:8086/write?db={}",
+            self.config.host, self.config.database
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .body(body.to_string())
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            self.metrics.increment_publish_errors();
+            anyhow::bail!("InfluxDB write returned status {}", response.status());
+        }
+
+        Ok(())
+    }
+}
+
+/// Format one `MetricsData` snapshot as InfluxDB line protocol:
+/// `welding_metrics,machine=<id> messages_processed=42i,... <unix_nanos>`.
+fn format_line(metrics: &MetricsData, machine_id: &str) -> String {
+    let timestamp_ns = chrono::Utc::now()
+        .timestamp_nanos_opt()
+        .unwrap_or_default();
+
+    format!(
+        "welding_metrics,machine={} messages_received={}i,messages_processed={}i,alerts_generated={}i,\
+processing_errors={}i,connection_errors={}i,publish_errors={}i,filter_hit_rate={},\
+avg_latency_ms={},p50_ms={},p90_ms={},p99_ms={} {}",
+        machine_id,
+        metrics.messages_received,
+        metrics.messages_processed,
+        metrics.alerts_generated,
+        metrics.processing_errors,
+        metrics.connection_errors,
+        metrics.publish_errors,
+        metrics.filter_hit_rate,
+        metrics.avg_processing_latency_ms,
+        metrics.p50_ms,
+        metrics.p90_ms,
+        metrics.p99_ms,
+        timestamp_ns,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InfluxConfig;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn sample_metrics() -> MetricsData {
+        MetricsData {
+            messages_received: 10,
+            messages_processed: 8,
+            alerts_generated: 2,
+            processing_errors: 1,
+            connection_errors: 3,
+            publish_errors: 4,
+            avg_processing_latency_ms: 1.5,
+            max_processing_latency_ms: 5,
+            p50_ms: 1.1,
+            p90_ms: 2.2,
+            p99_ms: 3.3,
+            uptime_seconds: 60,
+            filter_hit_rate: 25.0,
+            timestamp: "2025-01-01T00:00:00Z".to_string(),
+            reconnect_count: 0,
+            consecutive_connection_failures: 0,
+        }
+    }
+
+    #[test]
+    fn test_format_line_field_order_and_values() {
+        let line = format_line(&sample_metrics(), "processor-1");
+
+        let (fields, timestamp_ns) = line.rsplit_once(' ').expect("line has a trailing timestamp field");
+        assert_eq!(
+            fields,
+            "welding_metrics,machine=processor-1 messages_received=10i,messages_processed=8i,alerts_generated=2i,\
+processing_errors=1i,connection_errors=3i,publish_errors=4i,filter_hit_rate=25,\
+avg_latency_ms=1.5,p50_ms=1.1,p90_ms=2.2,p99_ms=3.3"
+        );
+        assert!(!timestamp_ns.is_empty() && timestamp_ns.chars().all(|c| c.is_ascii_digit()));
+    }
+
+    fn test_influx_config() -> InfluxConfig {
+        InfluxConfig {
+            host: "127.0.0.1".to_string(),
+            database: "welding".to_string(),
+            machine_id: "processor-1".to_string(),
+            export_interval_seconds: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_once_keeps_failed_batch_buffered_until_a_later_success() {
+        let metrics = Arc::new(MetricsCollector::new());
+        let exporter = MetricsExporter::new(test_influx_config(), metrics);
+
+        // Nothing is listening on the InfluxDB port yet, so the write fails
+        // and the batch must stay buffered instead of being dropped.
+        exporter.export_once().await;
+        assert_eq!(exporter.failed_batches.lock().await.len(), 1);
+
+        // Stand in for InfluxDB accepting the retried write.
+        let listener = TcpListener::bind("127.0.0.1:8086")
+            .await
+            .expect("failed to bind stand-in InfluxDB listener");
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.expect("stand-in server accept failed");
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            socket
+                .write_all(b"HTTP/1.1 204 No Content\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .expect("stand-in server write failed");
+        });
+
+        // The buffered batch from the first attempt, plus this export's own
+        // line, should now both be flushed on the successful write.
+        exporter.export_once().await;
+        server.await.expect("stand-in server task panicked");
+
+        assert_eq!(exporter.failed_batches.lock().await.len(), 0);
+    }
+}
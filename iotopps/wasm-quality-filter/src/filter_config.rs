@@ -0,0 +1,328 @@
+//! Host-driven hot-patching of filter thresholds via the WASM boundary.
+//! `thread_local!`, `HashMap`, and `wasm_bindgen` all require `std`, so this
+//! whole module is compiled only for `std` builds - `no_std` targets run the
+//! pure `filter_logic`/`rule_engine` alerting logic without this layer.
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+use crate::filter_logic::{CYCLE_TIME_THRESHOLD, SCRAP_QUALITY};
+
+/// How far below `cycle_time_threshold` a cycle time must fall to be
+/// classified "high" or "medium" severity; anything closer than
+/// `medium_deviation` is "low".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeverityBands {
+    pub high_deviation: f64,
+    pub medium_deviation: f64,
+}
+
+/// Runtime-tunable thresholds and impact mappings that used to be compile-time
+/// constants (`CYCLE_TIME_THRESHOLD`, `SCRAP_QUALITY`, the impact `match` in
+/// `QualityAnalyzer::estimate_impact`), so operators can retune the filter
+/// without redeploying the WASM module.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterConfig {
+    pub cycle_time_threshold: f64,
+    pub scrap_quality: String,
+    pub severity_bands: SeverityBands,
+    pub assembly_impact: HashMap<String, String>,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        let mut assembly_impact = HashMap::new();
+        assembly_impact.insert("FrameAssembly".to_string(), "critical".to_string());
+        assembly_impact.insert("EngineMount".to_string(), "critical".to_string());
+        assembly_impact.insert("WingJoint".to_string(), "high".to_string());
+        assembly_impact.insert("DockingPort".to_string(), "high".to_string());
+        assembly_impact.insert("HullSeam".to_string(), "medium".to_string());
+
+        Self {
+            cycle_time_threshold: CYCLE_TIME_THRESHOLD,
+            scrap_quality: SCRAP_QUALITY.to_string(),
+            severity_bands: SeverityBands {
+                high_deviation: 2.0,
+                medium_deviation: 1.0,
+            },
+            assembly_impact,
+        }
+    }
+}
+
+impl FilterConfig {
+    /// Mirrors `QualityAnalyzer::estimate_impact`, but driven by the
+    /// reconfigurable `assembly_impact` table instead of a hardcoded match.
+    pub fn estimate_impact(&self, assembly_type: &str) -> String {
+        self.assembly_impact
+            .get(assembly_type)
+            .cloned()
+            .unwrap_or_else(|| "low".to_string())
+    }
+
+    /// Apply an RFC 7386 JSON Merge Patch: object keys are overlaid
+    /// recursively, and a `null` value deletes the corresponding key.
+    pub fn apply_merge(&mut self, merge: &Value) -> Result<(), String> {
+        let mut current = serde_json::to_value(&*self).map_err(|e| e.to_string())?;
+        merge_patch(&mut current, merge);
+        *self = serde_json::from_value(current).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Apply a sequence of RFC 6902 JSON Patch `add`/`replace`/`remove`
+    /// operations at JSON-pointer paths.
+    pub fn apply_patch(&mut self, ops: &[PatchOp]) -> Result<(), String> {
+        let mut current = serde_json::to_value(&*self).map_err(|e| e.to_string())?;
+        for op in ops {
+            apply_patch_op(&mut current, op)?;
+        }
+        *self = serde_json::from_value(current).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+/// One operation from an RFC 6902 JSON Patch document. `move`, `copy`, and
+/// `test` are not needed for config hot-patching and are left unimplemented.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOp {
+    Add { path: String, value: Value },
+    Replace { path: String, value: Value },
+    Remove { path: String },
+}
+
+/// Recursively overlay `patch` onto `target` per RFC 7386: object keys merge
+/// recursively, `null` deletes a key, and any other value (including arrays)
+/// replaces the target wholesale.
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch_obj) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(serde_json::Map::new());
+    }
+    let target_obj = target.as_object_mut().expect("just ensured target is an object");
+
+    for (key, value) in patch_obj {
+        if value.is_null() {
+            target_obj.remove(key);
+        } else {
+            merge_patch(target_obj.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+fn split_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        return Err(format!("Invalid JSON pointer: {}", pointer));
+    }
+    Ok(pointer[1..]
+        .split('/')
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+fn navigate_mut<'a>(root: &'a mut Value, tokens: &[String]) -> Result<&'a mut Value, String> {
+    let mut current = root;
+    for token in tokens {
+        current = match current {
+            Value::Object(map) => map
+                .get_mut(token)
+                .ok_or_else(|| format!("Path segment not found: {}", token))?,
+            Value::Array(arr) => {
+                let index: usize = token
+                    .parse()
+                    .map_err(|_| format!("Invalid array index: {}", token))?;
+                arr.get_mut(index)
+                    .ok_or_else(|| format!("Array index out of bounds: {}", index))?
+            }
+            _ => return Err("Cannot navigate into a scalar value".to_string()),
+        };
+    }
+    Ok(current)
+}
+
+fn apply_patch_op(root: &mut Value, op: &PatchOp) -> Result<(), String> {
+    let (path, remove, value) = match op {
+        PatchOp::Add { path, value } => (path, false, Some(value.clone())),
+        PatchOp::Replace { path, value } => (path, false, Some(value.clone())),
+        PatchOp::Remove { path } => (path, true, None),
+    };
+    let is_add = matches!(op, PatchOp::Add { .. });
+
+    let tokens = split_pointer(path)?;
+    let (parent_tokens, last_token) = tokens
+        .split_last()
+        .ok_or_else(|| "Cannot operate on the document root".to_string())?;
+    let last_token = last_token.clone();
+    let parent = navigate_mut(root, parent_tokens)?;
+
+    match parent {
+        Value::Object(map) => {
+            if remove {
+                map.remove(&last_token)
+                    .ok_or_else(|| format!("Path not found: {}", path))?;
+            } else if !is_add && !map.contains_key(&last_token) {
+                return Err(format!("Path not found: {}", path));
+            } else {
+                map.insert(last_token, value.expect("non-remove op carries a value"));
+            }
+        }
+        Value::Array(arr) => {
+            let index = if last_token == "-" {
+                arr.len()
+            } else {
+                last_token
+                    .parse::<usize>()
+                    .map_err(|_| format!("Invalid array index: {}", last_token))?
+            };
+
+            if remove {
+                if index >= arr.len() {
+                    return Err(format!("Array index out of bounds: {}", index));
+                }
+                arr.remove(index);
+            } else if is_add {
+                if index > arr.len() {
+                    return Err(format!("Array index out of bounds: {}", index));
+                }
+                arr.insert(index, value.expect("add op carries a value"));
+            } else {
+                if index >= arr.len() {
+                    return Err(format!("Array index out of bounds: {}", index));
+                }
+                arr[index] = value.expect("replace op carries a value");
+            }
+        }
+        _ => return Err("Cannot set a field on a scalar value".to_string()),
+    }
+
+    Ok(())
+}
+
+thread_local! {
+    static FILTER_CONFIG: RefCell<FilterConfig> = RefCell::new(FilterConfig::default());
+}
+
+/// Push an RFC 7386 JSON Merge Patch onto the live `FilterConfig`. Returns
+/// `true` on success, `false` if `merge_json` is malformed or the result
+/// doesn't deserialize back into a valid config.
+#[wasm_bindgen]
+pub fn apply_filter_config_merge(merge_json: &str) -> bool {
+    let Ok(merge) = serde_json::from_str::<Value>(merge_json) else {
+        return false;
+    };
+    FILTER_CONFIG.with(|config| config.borrow_mut().apply_merge(&merge).is_ok())
+}
+
+/// Push an RFC 6902 JSON Patch document onto the live `FilterConfig`. Returns
+/// `true` on success, `false` if `patch_json` is malformed or any operation
+/// fails to apply.
+#[wasm_bindgen]
+pub fn apply_filter_config_patch(patch_json: &str) -> bool {
+    let Ok(ops) = serde_json::from_str::<Vec<PatchOp>>(patch_json) else {
+        return false;
+    };
+    FILTER_CONFIG.with(|config| config.borrow_mut().apply_patch(&ops).is_ok())
+}
+
+/// Read back the live `FilterConfig` as JSON, mainly for host-side debugging.
+#[wasm_bindgen]
+pub fn get_filter_config_json() -> String {
+    FILTER_CONFIG.with(|config| serde_json::to_string(&*config.borrow()).unwrap_or_default())
+}
+
+/// Clone the live `FilterConfig`, for building a `RuleSet` (via
+/// `RuleSet::from_config`) that reflects the latest hot-patched thresholds.
+pub fn current_snapshot() -> FilterConfig {
+    FILTER_CONFIG.with(|config| config.borrow().clone())
+}
+
+/// Look up `assembly_type`'s impact level in the live `FilterConfig`. This is
+/// what `QualityAnalyzer::estimate_impact` calls on `std` builds, so a
+/// `assembly_impact` merge patch actually changes alerting output instead of
+/// only the value `get_filter_config_json` reports back.
+pub fn estimate_impact(assembly_type: &str) -> String {
+    FILTER_CONFIG.with(|config| config.borrow().estimate_impact(assembly_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_filter_config_matches_original_constants() {
+        let config = FilterConfig::default();
+        assert_eq!(config.cycle_time_threshold, CYCLE_TIME_THRESHOLD);
+        assert_eq!(config.scrap_quality, SCRAP_QUALITY);
+        assert_eq!(config.estimate_impact("FrameAssembly"), "critical");
+        assert_eq!(config.estimate_impact("HullSeam"), "medium");
+        assert_eq!(config.estimate_impact("Unknown"), "low");
+    }
+
+    #[test]
+    fn test_apply_merge_overlays_and_deletes() {
+        let mut config = FilterConfig::default();
+        let merge: Value = serde_json::from_str(r#"{"cycle_time_threshold": 9.5, "assembly_impact": {"HullSeam": null, "NewPart": "critical"}}"#).unwrap();
+
+        config.apply_merge(&merge).unwrap();
+
+        assert_eq!(config.cycle_time_threshold, 9.5);
+        assert_eq!(config.estimate_impact("HullSeam"), "low");
+        assert_eq!(config.estimate_impact("NewPart"), "critical");
+        assert_eq!(config.estimate_impact("FrameAssembly"), "critical");
+    }
+
+    #[test]
+    fn test_apply_patch_add_replace_remove() {
+        let mut config = FilterConfig::default();
+        let ops = vec![
+            PatchOp::Replace {
+                path: "/cycle_time_threshold".to_string(),
+                value: Value::from(8.0),
+            },
+            PatchOp::Add {
+                path: "/assembly_impact/NewPart".to_string(),
+                value: Value::from("high"),
+            },
+            PatchOp::Remove {
+                path: "/assembly_impact/HullSeam".to_string(),
+            },
+        ];
+
+        config.apply_patch(&ops).unwrap();
+
+        assert_eq!(config.cycle_time_threshold, 8.0);
+        assert_eq!(config.estimate_impact("NewPart"), "high");
+        assert_eq!(config.estimate_impact("HullSeam"), "low");
+    }
+
+    #[test]
+    fn test_apply_patch_remove_missing_path_errors() {
+        let mut config = FilterConfig::default();
+        let ops = vec![PatchOp::Remove {
+            path: "/assembly_impact/DoesNotExist".to_string(),
+        }];
+
+        assert!(config.apply_patch(&ops).is_err());
+    }
+
+    #[test]
+    fn test_live_config_merge_is_visible_through_snapshot_and_estimate_impact() {
+        assert_eq!(estimate_impact("HullSeam"), "medium");
+
+        let merge: Value = serde_json::from_str(r#"{"assembly_impact": {"HullSeam": "critical"}}"#).unwrap();
+        assert!(apply_filter_config_merge(&merge.to_string()));
+
+        assert_eq!(estimate_impact("HullSeam"), "critical");
+        assert_eq!(current_snapshot().estimate_impact("HullSeam"), "critical");
+    }
+}
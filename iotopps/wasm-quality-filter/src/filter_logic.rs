@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
 use crate::message_parser::WeldingMessage;
 
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+#[cfg(not(feature = "std"))]
+use alloc::format;
+
 /// Quality control alert structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QualityControlAlert {
@@ -40,6 +44,49 @@ pub struct LineInfo {
 pub const CYCLE_TIME_THRESHOLD: f64 = 7.0;
 pub const SCRAP_QUALITY: &str = "scrap";
 
+/// Default time source for `std` builds, wrapping `chrono::Utc::now()`.
+/// `no_std` targets don't have a universal clock, so they supply their own
+/// `fn() -> i64` instead of using this one.
+#[cfg(feature = "std")]
+pub fn std_now_ns() -> i64 {
+    chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0)
+}
+
+/// Render nanoseconds since the Unix epoch as an RFC 3339 UTC timestamp
+/// without depending on `chrono`, so alert generation builds under `no_std`
+/// + `alloc`. Civil-date math is Howard Hinnant's `civil_from_days`.
+pub fn format_rfc3339_nanos(nanos: i64) -> String {
+    let total_seconds = nanos.div_euclid(1_000_000_000);
+    let nanos_remainder = nanos.rem_euclid(1_000_000_000);
+    let days = total_seconds.div_euclid(86_400);
+    let secs_of_day = total_seconds.rem_euclid(86_400);
+
+    let (year, month, day) = civil_from_days(days);
+    let hour = secs_of_day / 3600;
+    let minute = (secs_of_day % 3600) / 60;
+    let second = secs_of_day % 60;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:09}Z",
+        year, month, day, hour, minute, second, nanos_remainder
+    )
+}
+
+/// Days-since-epoch to proleptic-Gregorian (year, month, day).
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = (z - era * 146_097) as u64; // [0, 146096]
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // [0, 399]
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // [0, 365]
+    let mp = (5 * doy + 2) / 153; // [0, 11]
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32; // [1, 31]
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32; // [1, 12]
+    let year = if month <= 2 { y + 1 } else { y };
+    (year, month, day)
+}
+
 /// Main filter logic - determines if a quality control alert should be triggered
 /// 
 /// Condition: quality == "scrap" AND cycle_time < 7.0
@@ -48,9 +95,14 @@ pub fn should_trigger_alert(message: &WeldingMessage) -> bool {
     message.last_cycle_time < CYCLE_TIME_THRESHOLD
 }
 
-/// Generate a quality control alert from a welding message
-pub fn generate_quality_alert(message: &WeldingMessage) -> QualityControlAlert {
-    let now = Utc::now();
+/// Generate a quality control alert from a welding message.
+///
+/// `now_ns` supplies the current time as nanoseconds since the Unix epoch -
+/// callers on `std` targets can pass [`std_now_ns`], while `no_std` hosts
+/// (e.g. a gateway microcontroller) pass in whatever clock they have. This
+/// keeps the alerting logic itself free of a direct `chrono::Utc::now()`
+/// call, which needs a clock `std` doesn't provide everywhere.
+pub fn generate_quality_alert(message: &WeldingMessage, now_ns: fn() -> i64) -> QualityControlAlert {
     let severity = determine_severity(message);
     let recommended_action = determine_recommended_action(message);
     let line_info = message.get_line_info()
@@ -59,7 +111,7 @@ pub fn generate_quality_alert(message: &WeldingMessage) -> QualityControlAlert {
     QualityControlAlert {
         alert_type: "quality_control".to_string(),
         source_machine: message.machine_id.clone(),
-        timestamp: now.to_rfc3339(),
+        timestamp: format_rfc3339_nanos(now_ns()),
         trigger_conditions: TriggerConditions {
             quality: message.quality.clone(),
             cycle_time: message.last_cycle_time,
@@ -108,7 +160,17 @@ impl QualityAnalyzer {
         message.last_cycle_time < 5.0 && message.quality == SCRAP_QUALITY
     }
 
-    /// Estimate the impact level of the quality issue
+    /// Estimate the impact level of the quality issue. On `std` builds this
+    /// reads the live, hot-patchable `FilterConfig` (see `filter_config`), so
+    /// an `assembly_impact` merge/patch actually changes alerting output;
+    /// `no_std` builds have no config layer to read, so they fall back to
+    /// the original fixed mapping.
+    #[cfg(feature = "std")]
+    pub fn estimate_impact(message: &WeldingMessage) -> String {
+        crate::filter_config::estimate_impact(&message.assembly_type)
+    }
+
+    #[cfg(not(feature = "std"))]
     pub fn estimate_impact(message: &WeldingMessage) -> String {
         match message.assembly_type.as_str() {
             "FrameAssembly" | "EngineMount" => "critical".to_string(),
@@ -196,7 +258,7 @@ mod tests {
     #[test]
     fn test_generate_quality_alert() {
         let message = create_test_message("scrap", 6.0);
-        let alert = generate_quality_alert(&message);
+        let alert = generate_quality_alert(&message, std_now_ns);
 
         assert_eq!(alert.alert_type, "quality_control");
         assert_eq!(alert.source_machine, "LINE-1-STATION-C-01");
@@ -207,6 +269,13 @@ mod tests {
         assert!(alert.line_info.is_some());
     }
 
+    #[test]
+    fn test_format_rfc3339_nanos_epoch_and_offset() {
+        assert_eq!(format_rfc3339_nanos(0), "1970-01-01T00:00:00.000000000Z");
+        // 2025-12-02T15:30:00Z
+        assert_eq!(format_rfc3339_nanos(1_764_689_400_000_000_000), "2025-12-02T15:30:00.000000000Z");
+    }
+
     #[test]
     fn test_quality_analyzer_equipment_health() {
         let message = create_test_message("scrap", 4.0);
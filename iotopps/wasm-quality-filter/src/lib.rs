@@ -1,27 +1,85 @@
+//! The alerting core (`message_parser`, `filter_logic`, `rule_engine`) builds
+//! against `core` + `alloc` so it can also run on `no_std` gateway
+//! microcontrollers; the FFI/WASM glue below it needs `std` and is gated
+//! behind the (default-enabled) `std` feature.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(feature = "std")]
 use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
 use serde_json;
+#[cfg(feature = "std")]
 use std::ffi::{CStr, CString};
+#[cfg(feature = "std")]
 use std::os::raw::{c_char, c_int};
 
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+
 mod message_parser;
 mod filter_logic;
+#[cfg(feature = "std")]
+mod filter_config;
+mod rule_engine;
+#[cfg(feature = "std")]
+mod protobuf;
+#[cfg(feature = "std")]
+pub mod stream;
+#[cfg(feature = "std")]
+pub mod oee;
 
 use message_parser::WeldingMessage;
 use filter_logic::QualityControlAlert;
+use rule_engine::RuleSet;
+
+/// Tagged outcome of processing one welding message, letting callers
+/// distinguish a malformed message, a serialization failure, and a clean
+/// "no alert" pass instead of collapsing all three into a null pointer.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ProcessOutcome {
+    Alert { payload: String },
+    NoAlert,
+    ParseError { message: String, field: Option<String> },
+    SerializeError { message: String },
+}
+
+/// Best-effort extraction of the offending field name from a serde_json
+/// "missing field" error message, so `ParseError.field` is populated when
+/// we can tell which one it was.
+fn extract_missing_field(message: &str) -> Option<String> {
+    let marker = "missing field `";
+    let start = message.find(marker)? + marker.len();
+    let end = message[start..].find('`')?;
+    Some(message[start..start + end].to_string())
+}
 
 // WASM bindgen exports for JavaScript environments
+#[cfg(feature = "std")]
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_namespace = console)]
     fn log(s: &str);
 }
 
-// Macro for console logging from WASM
+// Macro for console logging from WASM. On `no_std` builds there's no
+// `console` to log to, so it's a no-op that still evaluates its arguments
+// (avoiding unused-value warnings at call sites shared with `std` builds).
+#[cfg(feature = "std")]
 macro_rules! console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
 
+#[cfg(not(feature = "std"))]
+macro_rules! console_log {
+    ($($t:tt)*) => {{ let _ = format_args!($($t)*); }}
+}
+
 // C-compatible interface for non-JavaScript WASM runtimes
+#[cfg(feature = "std")]
 #[no_mangle]
 pub extern "C" fn process_message(input_ptr: *const c_char) -> *mut c_char {
     if input_ptr.is_null() {
@@ -34,7 +92,7 @@ pub extern "C" fn process_message(input_ptr: *const c_char) -> *mut c_char {
         Err(_) => return std::ptr::null_mut(),
     };
 
-    match process_welding_message(input) {
+    match process_welding_message(input, &RuleSet::from_config(&filter_config::current_snapshot()), filter_logic::std_now_ns) {
         Some(result) => {
             match CString::new(result) {
                 Ok(c_string) => c_string.into_raw(),
@@ -46,61 +104,125 @@ pub extern "C" fn process_message(input_ptr: *const c_char) -> *mut c_char {
 }
 
 // Free memory allocated by process_message
+#[cfg(feature = "std")]
 #[no_mangle]
 pub extern "C" fn free_string(ptr: *mut c_char) {
     if !ptr.is_null() {
-        unsafe { 
-            let _ = CString::from_raw(ptr); 
+        unsafe {
+            let _ = CString::from_raw(ptr);
         }
     }
 }
 
 // JavaScript-compatible interface
+#[cfg(feature = "std")]
 #[wasm_bindgen]
 pub fn process_welding_message_js(input: &str) -> Option<String> {
-    process_welding_message(input)
+    process_welding_message(input, &RuleSet::from_config(&filter_config::current_snapshot()), filter_logic::std_now_ns)
 }
 
-// Core processing logic
-pub fn process_welding_message(input: &str) -> Option<String> {
+// Core processing logic. `now_ns` is the alert timestamp's time source - see
+// `filter_logic::generate_quality_alert` - and this whole function builds
+// under `no_std` + `alloc` so it can run on constrained devices upstream of
+// the WASM pipeline.
+//
+// A message may trip several rules in `rule_set`, but the WASM/FFI boundary
+// returns a single JSON payload, so only the first matching alert is
+// serialized - the default ruleset's tiers are mutually exclusive by cycle
+// time range, so this never drops an alert for the built-in behavior.
+pub fn process_welding_message_outcome(input: &str, rule_set: &RuleSet, now_ns: fn() -> i64) -> ProcessOutcome {
     console_log!("Processing welding message: {}", input);
-    
+
     // Parse the incoming welding message
     let welding_message = match message_parser::parse_welding_message(input) {
         Ok(msg) => msg,
         Err(e) => {
             console_log!("Failed to parse welding message: {}", e);
-            return None;
+            let message = e.to_string();
+            let field = extract_missing_field(&message);
+            return ProcessOutcome::ParseError { message, field };
         }
     };
 
-    console_log!("Parsed message - Machine: {}, Quality: {}, Cycle Time: {}", 
+    console_log!("Parsed message - Machine: {}, Quality: {}, Cycle Time: {}",
                  welding_message.machine_id, welding_message.quality, welding_message.last_cycle_time);
 
-    // Apply quality filter logic
-    if filter_logic::should_trigger_alert(&welding_message) {
-        console_log!("Quality alert triggered for machine: {}", welding_message.machine_id);
-        
-        // Generate quality control alert
-        let alert = filter_logic::generate_quality_alert(&welding_message);
-        
-        match serde_json::to_string(&alert) {
-            Ok(json) => {
-                console_log!("Generated quality alert: {}", json);
-                Some(json)
-            },
-            Err(e) => {
-                console_log!("Failed to serialize quality alert: {}", e);
-                None
+    // Apply the configured quality rules
+    let alerts = rule_set.evaluate(&welding_message, now_ns);
+
+    match alerts.into_iter().next() {
+        Some(alert) => {
+            console_log!("Quality alert triggered for machine: {}", welding_message.machine_id);
+
+            match serde_json::to_string(&alert) {
+                Ok(json) => {
+                    console_log!("Generated quality alert: {}", json);
+                    ProcessOutcome::Alert { payload: json }
+                },
+                Err(e) => {
+                    console_log!("Failed to serialize quality alert: {}", e);
+                    ProcessOutcome::SerializeError { message: e.to_string() }
+                }
             }
         }
-    } else {
-        console_log!("No quality alert needed for machine: {}", welding_message.machine_id);
-        None
+        None => {
+            console_log!("No quality alert needed for machine: {}", welding_message.machine_id);
+            ProcessOutcome::NoAlert
+        }
+    }
+}
+
+// Back-compat surface for existing callers that only distinguish "alert" from
+// "no alert" - prefer `process_welding_message_outcome` for new integrations
+// so parse/serialize failures aren't swallowed into `None`.
+pub fn process_welding_message(input: &str, rule_set: &RuleSet, now_ns: fn() -> i64) -> Option<String> {
+    match process_welding_message_outcome(input, rule_set, now_ns) {
+        ProcessOutcome::Alert { payload } => Some(payload),
+        ProcessOutcome::NoAlert | ProcessOutcome::ParseError { .. } | ProcessOutcome::SerializeError { .. } => None,
+    }
+}
+
+fn serialize_outcome(outcome: &ProcessOutcome) -> String {
+    serde_json::to_string(outcome).unwrap_or_else(|_| {
+        r#"{"status":"serialize_error","message":"failed to serialize outcome envelope"}"#.to_string()
+    })
+}
+
+// JavaScript-compatible interface that always returns the tagged outcome
+// envelope, so a host can route parse/serialize failures to a dead-letter
+// queue instead of treating them the same as "no alert".
+#[cfg(feature = "std")]
+#[wasm_bindgen]
+pub fn process_welding_message_outcome_js(input: &str) -> String {
+    serialize_outcome(&process_welding_message_outcome(input, &RuleSet::from_config(&filter_config::current_snapshot()), filter_logic::std_now_ns))
+}
+
+// C-compatible interface that always returns the tagged outcome envelope as a
+// non-null pointer - see `process_message` for the back-compat, null-on-no-alert
+// surface.
+#[cfg(feature = "std")]
+#[no_mangle]
+pub extern "C" fn process_message_with_outcome(input_ptr: *const c_char) -> *mut c_char {
+    if input_ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let c_str = unsafe { CStr::from_ptr(input_ptr) };
+    let input = match c_str.to_str() {
+        Ok(s) => s,
+        Err(_) => return std::ptr::null_mut(),
+    };
+
+    let json = serialize_outcome(&process_welding_message_outcome(input, &RuleSet::from_config(&filter_config::current_snapshot()), filter_logic::std_now_ns));
+
+    match CString::new(json) {
+        Ok(c_string) => c_string.into_raw(),
+        Err(_) => std::ptr::null_mut(),
     }
 }
 
 // Initialize the WASM module
+#[cfg(feature = "std")]
 #[wasm_bindgen(start)]
 pub fn main() {
     console_log!("WASM Quality Filter Module initialized");
@@ -123,7 +245,7 @@ mod tests {
             "station_id": "LINE-1-STATION-C"
         }"#;
 
-        let result = process_welding_message(input);
+        let result = process_welding_message(input, &RuleSet::default_ruleset(), filter_logic::std_now_ns);
         assert!(result.is_some(), "Should generate quality alert for scrap with cycle_time < 7");
         
         let alert_json = result.unwrap();
@@ -144,7 +266,7 @@ mod tests {
             "station_id": "LINE-1-STATION-C"
         }"#;
 
-        let result = process_welding_message(input);
+        let result = process_welding_message(input, &RuleSet::default_ruleset(), filter_logic::std_now_ns);
         assert!(result.is_none(), "Should not generate alert for scrap with cycle_time >= 7");
     }
 
@@ -161,14 +283,68 @@ mod tests {
             "station_id": "LINE-1-STATION-C"
         }"#;
 
-        let result = process_welding_message(input);
+        let result = process_welding_message(input, &RuleSet::default_ruleset(), filter_logic::std_now_ns);
         assert!(result.is_none(), "Should not generate alert for good quality");
     }
 
     #[test]
     fn test_process_invalid_json() {
         let input = r#"{"invalid": json"#;
-        let result = process_welding_message(input);
+        let result = process_welding_message(input, &RuleSet::default_ruleset(), filter_logic::std_now_ns);
         assert!(result.is_none(), "Should handle invalid JSON gracefully");
     }
+
+    #[test]
+    fn test_outcome_alert() {
+        let input = r#"{
+            "machine_id": "LINE-1-STATION-C-01",
+            "timestamp": "2025-12-02T15:30:00Z",
+            "status": "running",
+            "last_cycle_time": 6.5,
+            "quality": "scrap",
+            "assembly_type": "FrameAssembly",
+            "assembly_id": "FA-001-2025-001",
+            "station_id": "LINE-1-STATION-C"
+        }"#;
+
+        match process_welding_message_outcome(input, &RuleSet::default_ruleset(), filter_logic::std_now_ns) {
+            ProcessOutcome::Alert { payload } => assert!(payload.contains("quality_control")),
+            other => panic!("Expected Alert, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_outcome_no_alert() {
+        let input = r#"{
+            "machine_id": "LINE-1-STATION-C-03",
+            "timestamp": "2025-12-02T15:30:00Z",
+            "status": "running",
+            "last_cycle_time": 6.0,
+            "quality": "good",
+            "assembly_type": "FrameAssembly",
+            "assembly_id": "FA-001-2025-003",
+            "station_id": "LINE-1-STATION-C"
+        }"#;
+
+        let outcome = process_welding_message_outcome(input, &RuleSet::default_ruleset(), filter_logic::std_now_ns);
+        assert_eq!(outcome, ProcessOutcome::NoAlert);
+    }
+
+    #[test]
+    fn test_outcome_parse_error_distinguishes_malformed_input() {
+        let input = r#"{"invalid": json"#;
+        match process_welding_message_outcome(input, &RuleSet::default_ruleset(), filter_logic::std_now_ns) {
+            ProcessOutcome::ParseError { .. } => {}
+            other => panic!("Expected ParseError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_outcome_parse_error_missing_field_is_identified() {
+        let input = r#"{"machine_id": "LINE-1-STATION-C-01"}"#;
+        match process_welding_message_outcome(input, &RuleSet::default_ruleset(), filter_logic::std_now_ns) {
+            ProcessOutcome::ParseError { field, .. } => assert!(field.is_some()),
+            other => panic!("Expected ParseError, got {:?}", other),
+        }
+    }
 }
\ No newline at end of file
@@ -1,6 +1,14 @@
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use chrono::{DateTime, Utc};
 
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use alloc::format;
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct WeldingMessage {
     pub machine_id: String,
@@ -14,7 +22,10 @@ pub struct WeldingMessage {
 }
 
 impl WeldingMessage {
-    /// Parse timestamp to DateTime if possible
+    /// Parse timestamp to DateTime if possible. Needs `chrono`'s clock-backed
+    /// `DateTime`, so it's only available on `std` builds; `no_std` targets
+    /// that care about the raw timestamp can still read `self.timestamp`.
+    #[cfg(feature = "std")]
     pub fn get_timestamp(&self) -> Result<DateTime<Utc>, chrono::ParseError> {
         DateTime::parse_from_rfc3339(&self.timestamp)
             .map(|dt| dt.with_timezone(&Utc))
@@ -22,13 +33,24 @@ impl WeldingMessage {
 
     /// Check if the message represents a valid welding operation
     pub fn is_valid_operation(&self) -> bool {
-        !self.machine_id.is_empty() 
+        !self.machine_id.is_empty()
             && !self.quality.is_empty()
             && self.last_cycle_time > 0.0
             && matches!(self.quality.as_str(), "good" | "scrap" | "rework")
             && matches!(self.status.as_str(), "running" | "idle" | "cooling" | "faulted")
     }
 
+    /// Like `is_valid_operation`, but additionally requires `timestamp` to be
+    /// parseable via `parse_timestamp_flexible` when `require_timestamp` is
+    /// `true`. A separate method (rather than changing `is_valid_operation`
+    /// itself) keeps existing callers - which tolerate PLCs with slightly
+    /// nonconforming clocks - unaffected.
+    #[cfg(feature = "std")]
+    pub fn is_valid_operation_with_timestamp(&self, require_timestamp: bool) -> bool {
+        self.is_valid_operation()
+            && (!require_timestamp || parse_timestamp_flexible(&self.timestamp).is_ok())
+    }
+
     /// Extract line and station information from machine_id
     pub fn get_line_info(&self) -> Option<(String, String)> {
         // Expected format: "LINE-{line}-STATION-{station}-{machine_num}"
@@ -41,17 +63,279 @@ impl WeldingMessage {
     }
 }
 
+/// The result of `parse_timestamp_flexible`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlexibleTimestamp {
+    pub datetime: DateTime<Utc>,
+    /// `true` if `timestamp` carried no UTC offset and one was assumed,
+    /// rather than being stated explicitly (`Z`, `+HH:MM`, or `+HHMM`).
+    pub offset_was_assumed: bool,
+}
+
+/// Parse a timestamp more tolerantly than `DateTime::parse_from_rfc3339`:
+/// the date/time separator may be `T` or a space, fractional seconds may
+/// have any number of digits (truncated/padded to nanoseconds), and the
+/// offset may be `Z`, `±HH:MM`, `±HHMM`, or absent (treated as UTC, flagged
+/// via `offset_was_assumed`). This keeps cycle records from PLCs with
+/// slightly nonconforming clocks from being dropped outright.
+#[cfg(feature = "std")]
+pub fn parse_timestamp_flexible(input: &str) -> Result<FlexibleTimestamp, chrono::ParseError> {
+    match normalize_to_rfc3339(input) {
+        Some((normalized, offset_was_assumed)) => {
+            let dt = DateTime::parse_from_rfc3339(&normalized)?;
+            Ok(FlexibleTimestamp { datetime: dt.with_timezone(&Utc), offset_was_assumed })
+        }
+        // Not a shape we recognize - fall back to a strict parse so the
+        // caller still gets a meaningful `chrono::ParseError` instead of one
+        // fabricated for a string chrono never actually saw.
+        None => {
+            let dt = DateTime::parse_from_rfc3339(input)?;
+            Ok(FlexibleTimestamp { datetime: dt.with_timezone(&Utc), offset_was_assumed: false })
+        }
+    }
+}
+
+/// Rewrite a tolerant timestamp into strict RFC 3339, returning the
+/// normalized string and whether its offset was assumed. Returns `None` if
+/// `input` doesn't match the expected `YYYY-MM-DD(T| )HH:MM:SS[.fff][offset]`
+/// shape at all.
+#[cfg(feature = "std")]
+fn normalize_to_rfc3339(input: &str) -> Option<(String, bool)> {
+    if input.len() < 19 {
+        return None;
+    }
+    let (date, rest0) = input.split_at(10);
+    if date.as_bytes().get(4) != Some(&b'-') || date.as_bytes().get(7) != Some(&b'-') {
+        return None;
+    }
+
+    let sep = rest0.as_bytes().first()?;
+    if !matches!(sep, b'T' | b't' | b' ') {
+        return None;
+    }
+    let rest = &rest0[1..];
+
+    let offset_start = rest
+        .char_indices()
+        .skip(8) // past "HH:MM:SS"
+        .find(|&(_, c)| matches!(c, 'Z' | 'z' | '+' | '-'))
+        .map(|(idx, _)| idx);
+    let (time_part, offset_part) = match offset_start {
+        Some(idx) => (&rest[..idx], &rest[idx..]),
+        None => (rest, ""),
+    };
+
+    let (whole, frac) = match time_part.find('.') {
+        Some(idx) => (&time_part[..idx], &time_part[idx + 1..]),
+        None => (time_part, ""),
+    };
+    if whole.len() != 8 {
+        return None;
+    }
+
+    let mut frac9 = String::with_capacity(9);
+    for c in frac.chars().take(9) {
+        if !c.is_ascii_digit() {
+            return None;
+        }
+        frac9.push(c);
+    }
+    while frac9.len() < 9 {
+        frac9.push('0');
+    }
+
+    let (offset, offset_was_assumed) = normalize_offset(offset_part)?;
+
+    Some((format!("{}T{}.{}{}", date, whole, frac9, offset), offset_was_assumed))
+}
+
+/// Normalize a UTC offset suffix to `Z` or `±HH:MM`, reporting whether it was
+/// absent (and therefore assumed to be UTC) in the input.
+#[cfg(feature = "std")]
+fn normalize_offset(offset: &str) -> Option<(String, bool)> {
+    if offset.is_empty() {
+        return Some(("Z".to_string(), true));
+    }
+    if offset.eq_ignore_ascii_case("z") {
+        return Some(("Z".to_string(), false));
+    }
+
+    let sign = offset.as_bytes().first().copied()?;
+    if !matches!(sign, b'+' | b'-') {
+        return None;
+    }
+    let digits: String = offset[1..].chars().filter(|c| *c != ':').collect();
+    if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((format!("{}{}:{}", sign as char, &digits[..2], &digits[2..]), false))
+}
+
+/// Why `parse_welding_message` failed. Kept as a small enum
+/// rather than `Box<dyn std::error::Error>` so this module builds on `no_std`
+/// + `alloc` targets (gateway microcontrollers) instead of requiring `std`.
+#[derive(Debug)]
+pub enum ParseError {
+    Json(serde_json::Error),
+    #[cfg(feature = "std")]
+    Protobuf(prost::DecodeError),
+    InvalidOperation,
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParseError::Json(e) => write!(f, "{}", e),
+            #[cfg(feature = "std")]
+            ParseError::Protobuf(e) => write!(f, "{}", e),
+            ParseError::InvalidOperation => write!(f, "Invalid welding operation data"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
 /// Parse a JSON string into a WeldingMessage
-pub fn parse_welding_message(json_str: &str) -> Result<WeldingMessage, Box<dyn std::error::Error>> {
-    let message: WeldingMessage = serde_json::from_str(json_str)?;
-    
+pub fn parse_welding_message(json_str: &str) -> Result<WeldingMessage, ParseError> {
+    let message: WeldingMessage = serde_json::from_str(json_str).map_err(ParseError::Json)?;
+
     if !message.is_valid_operation() {
-        return Err("Invalid welding operation data".into());
+        return Err(ParseError::InvalidOperation);
     }
-    
+
     Ok(message)
 }
 
+/// One field whose value `parse_welding_message_lossy` had to repair.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepairWarning {
+    pub field: String,
+    pub reason: String,
+}
+
+/// Like `parse_welding_message`, but tolerates malformed `\uXXXX` escapes in
+/// the raw JSON text (e.g. lone UTF-16 surrogates copied from a Windows
+/// barcode scanner) instead of hard-failing on them. Unpaired surrogate
+/// escapes are replaced with the replacement character before parsing, and
+/// each repair is reported as a `RepairWarning` naming the nearest preceding
+/// JSON key, so operators can audit which records were sanitized.
+pub fn parse_welding_message_lossy(json_str: &str) -> Result<(WeldingMessage, Vec<RepairWarning>), ParseError> {
+    let (sanitized, warnings) = sanitize_lone_surrogates(json_str);
+    let message: WeldingMessage = serde_json::from_str(&sanitized).map_err(ParseError::Json)?;
+
+    if !message.is_valid_operation() {
+        return Err(ParseError::InvalidOperation);
+    }
+
+    Ok((message, warnings))
+}
+
+/// Scan `input` for `\uXXXX` escapes that form an unpaired UTF-16 surrogate
+/// and replace each with `\ufffd` (the JSON escape for the replacement
+/// character U+FFFD), leaving everything else - including valid surrogate
+/// pairs - untouched. Tracks the object key in effect at each point in the
+/// scan (rather than searching backward from a repaired escape) so a `:`
+/// inside a value itself - e.g. the one in a `"timestamp"` field's own
+/// `"2025-12-02T15:30:00Z"` - is never mistaken for the key/value separator.
+fn sanitize_lone_surrogates(input: &str) -> (String, Vec<RepairWarning>) {
+    let bytes = input.as_bytes();
+    let mut out = String::with_capacity(input.len());
+    let mut warnings = Vec::new();
+    let mut i = 0;
+
+    let mut in_string = false;
+    let mut string_start = 0usize;
+    let mut current_key = "unknown".to_string();
+
+    while i < bytes.len() {
+        if let Some(unit) = read_unicode_escape(bytes, i) {
+            if (0xD800..=0xDBFF).contains(&unit) {
+                if let Some(next) = read_unicode_escape(bytes, i + 6) {
+                    if (0xDC00..=0xDFFF).contains(&next) {
+                        out.push_str(&input[i..i + 12]);
+                        i += 12;
+                        continue;
+                    }
+                }
+                out.push_str("\\ufffd");
+                warnings.push(RepairWarning {
+                    field: current_key.clone(),
+                    reason: "unpaired high surrogate in \\u escape".to_string(),
+                });
+                i += 6;
+                continue;
+            }
+
+            if (0xDC00..=0xDFFF).contains(&unit) {
+                out.push_str("\\ufffd");
+                warnings.push(RepairWarning {
+                    field: current_key.clone(),
+                    reason: "unpaired low surrogate in \\u escape".to_string(),
+                });
+                i += 6;
+                continue;
+            }
+
+            // A valid, non-surrogate \u escape - copy through untouched.
+            out.push_str(&input[i..i + 6]);
+            i += 6;
+            continue;
+        }
+
+        let ch = input[i..].chars().next().expect("i is a char boundary");
+
+        if in_string {
+            if ch == '\\' {
+                // Copy the escaped pair through as a unit so an escaped
+                // quote (`\"`) in the string body isn't mistaken for its
+                // closing quote.
+                out.push(ch);
+                i += ch.len_utf8();
+                if let Some(escaped) = input[i..].chars().next() {
+                    out.push(escaped);
+                    i += escaped.len_utf8();
+                }
+                continue;
+            }
+            if ch == '"' {
+                in_string = false;
+                if next_non_whitespace_is_colon(input, i + 1) {
+                    current_key = input[string_start..i].to_string();
+                }
+            }
+        } else if ch == '"' {
+            in_string = true;
+            string_start = i + 1;
+        }
+
+        out.push(ch);
+        i += ch.len_utf8();
+    }
+
+    (out, warnings)
+}
+
+/// Whether the first non-whitespace byte at or after `from` is `:` - the
+/// signal that the string literal which just closed is an object key rather
+/// than a value.
+fn next_non_whitespace_is_colon(input: &str, from: usize) -> bool {
+    input[from..].trim_start().starts_with(':')
+}
+
+/// Read the `\uXXXX` escape starting at byte offset `start`, if any, without
+/// judging whether it's a valid standalone codepoint or half of a surrogate
+/// pair - callers decide that from the returned code unit.
+fn read_unicode_escape(bytes: &[u8], start: usize) -> Option<u16> {
+    if bytes.get(start) != Some(&b'\\') || bytes.get(start + 1) != Some(&b'u') {
+        return None;
+    }
+    let hex = bytes.get(start + 2..start + 6)?;
+    let hex_str = core::str::from_utf8(hex).ok()?;
+    u16::from_str_radix(hex_str, 16).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,4 +449,134 @@ mod tests {
         message.machine_id = "".to_string();
         assert!(!message.is_valid_operation());
     }
+
+    #[test]
+    fn test_parse_lossy_passes_through_valid_json_unchanged() {
+        let json = r#"{
+            "machine_id": "LINE-1-STATION-C-01",
+            "timestamp": "2025-12-02T15:30:00Z",
+            "status": "running",
+            "last_cycle_time": 6.5,
+            "quality": "scrap",
+            "assembly_type": "FrameAssembly",
+            "assembly_id": "FA-001-2025-001",
+            "station_id": "LINE-1-STATION-C"
+        }"#;
+
+        let (message, warnings) = parse_welding_message_lossy(json).unwrap();
+        assert_eq!(message.assembly_id, "FA-001-2025-001");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_lossy_repairs_unpaired_surrogate() {
+        let json = r#"{
+            "machine_id": "LINE-1-STATION-C-01",
+            "timestamp": "2025-12-02T15:30:00Z",
+            "status": "running",
+            "last_cycle_time": 6.5,
+            "quality": "scrap",
+            "assembly_type": "FrameAssembly",
+            "assembly_id": "FA-\uD800-001",
+            "station_id": "LINE-1-STATION-C"
+        }"#;
+
+        assert!(parse_welding_message(json).is_err(), "strict parse should reject the lone surrogate");
+
+        let (message, warnings) = parse_welding_message_lossy(json).unwrap();
+        assert!(message.assembly_id.contains('\u{FFFD}'));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "assembly_id");
+    }
+
+    #[test]
+    fn test_parse_lossy_attributes_repair_to_key_not_a_colon_in_the_value() {
+        let json = r#"{
+            "machine_id": "LINE-1-STATION-C-01",
+            "timestamp": "2025-12-02T15:30:\uDC00Z",
+            "status": "running",
+            "last_cycle_time": 6.5,
+            "quality": "scrap",
+            "assembly_type": "FrameAssembly",
+            "assembly_id": "FA-001",
+            "station_id": "LINE-1-STATION-C"
+        }"#;
+
+        let (_, warnings) = parse_welding_message_lossy(json).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "timestamp");
+    }
+
+    #[test]
+    fn test_parse_lossy_still_rejects_invalid_operation() {
+        let json = r#"{
+            "machine_id": "LINE-1-STATION-C-01",
+            "timestamp": "2025-12-02T15:30:00Z",
+            "status": "running",
+            "last_cycle_time": 6.5,
+            "quality": "invalid_quality",
+            "assembly_type": "FrameAssembly",
+            "assembly_id": "FA-001-2025-001",
+            "station_id": "LINE-1-STATION-C"
+        }"#;
+
+        assert!(parse_welding_message_lossy(json).is_err());
+    }
+
+    #[test]
+    fn test_parse_timestamp_flexible_space_separator_and_offset() {
+        let result = parse_timestamp_flexible("2025-12-02 15:30:00+02:00").unwrap();
+        assert!(!result.offset_was_assumed);
+        assert_eq!(result.datetime.to_rfc3339(), "2025-12-02T13:30:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_timestamp_flexible_arbitrary_fractional_precision() {
+        let short = parse_timestamp_flexible("2025-12-02T15:30:00.5Z").unwrap();
+        let long = parse_timestamp_flexible("2025-12-02T15:30:00.500000000Z").unwrap();
+        assert_eq!(short.datetime, long.datetime);
+
+        let truncated = parse_timestamp_flexible("2025-12-02T15:30:00.123456789123Z").unwrap();
+        assert_eq!(truncated.datetime.timestamp_subsec_nanos(), 123_456_789);
+    }
+
+    #[test]
+    fn test_parse_timestamp_flexible_numeric_offset_without_colon() {
+        let without_colon = parse_timestamp_flexible("2025-12-02T15:30:00+0200").unwrap();
+        assert!(!without_colon.offset_was_assumed);
+        assert_eq!(without_colon.datetime.to_rfc3339(), "2025-12-02T13:30:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_timestamp_flexible_missing_offset_is_assumed_utc() {
+        let result = parse_timestamp_flexible("2025-12-02T15:30:00").unwrap();
+        assert!(result.offset_was_assumed);
+        assert_eq!(result.datetime.to_rfc3339(), "2025-12-02T15:30:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_timestamp_flexible_rejects_garbage() {
+        assert!(parse_timestamp_flexible("not a timestamp").is_err());
+    }
+
+    #[test]
+    fn test_is_valid_operation_with_timestamp() {
+        let mut message = WeldingMessage {
+            machine_id: "LINE-1-STATION-C-01".to_string(),
+            timestamp: "2025-12-02T15:30:00Z".to_string(),
+            status: "running".to_string(),
+            last_cycle_time: 6.5,
+            quality: "scrap".to_string(),
+            assembly_type: "FrameAssembly".to_string(),
+            assembly_id: "FA-001-2025-001".to_string(),
+            station_id: "LINE-1-STATION-C".to_string(),
+        };
+
+        assert!(message.is_valid_operation_with_timestamp(true));
+
+        message.timestamp = "not a timestamp".to_string();
+        assert!(message.is_valid_operation(), "unrelated validity checks are unaffected");
+        assert!(!message.is_valid_operation_with_timestamp(true));
+        assert!(message.is_valid_operation_with_timestamp(false));
+    }
 }
\ No newline at end of file
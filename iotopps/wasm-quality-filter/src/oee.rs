@@ -0,0 +1,251 @@
+//! Rolling OEE/quality aggregation keyed on `(line, station)`, fed by
+//! `ingest`ing validated `WeldingMessage`s (one at a time, or via
+//! `stream::WeldingStream`) so a dashboard can pull current figures with
+//! `snapshot()` instead of replaying history on every refresh.
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::message_parser::WeldingMessage;
+
+/// How far back `StationStats::trailing_15_min` looks.
+fn trailing_window() -> Duration {
+    Duration::minutes(15)
+}
+
+/// Length of a shift bucket; also how long a sample is retained before it's
+/// evicted, since nothing past a shift boundary is ever reported.
+fn shift_length() -> Duration {
+    Duration::hours(8)
+}
+
+/// Rolling metrics for one `(line, station)` pair over a single time window.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WindowStats {
+    pub good_count: u64,
+    pub scrap_count: u64,
+    pub rework_count: u64,
+    pub mean_cycle_time: f64,
+    pub p95_cycle_time: f64,
+    /// Share (0.0-1.0) of the window's elapsed time spent with the station's
+    /// status `idle` or `faulted`, derived from consecutive status
+    /// transitions rather than a separate "downtime" signal.
+    pub fault_idle_share: f64,
+    /// good_count / (good_count + scrap_count + rework_count), or 0.0 if the
+    /// window has no samples yet.
+    pub first_pass_yield: f64,
+}
+
+/// A `(line, station)`'s latest rolling metrics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StationStats {
+    pub line: String,
+    pub station: String,
+    pub trailing_15_min: WindowStats,
+    pub current_shift: WindowStats,
+}
+
+struct Sample {
+    timestamp: DateTime<Utc>,
+    status: String,
+    quality: String,
+    cycle_time: f64,
+}
+
+/// Consumes a batch or stream of `WeldingMessage`s and keeps rolling OEE and
+/// quality metrics per `(line, station)`.
+#[derive(Default)]
+pub struct OeeAccumulator {
+    stations: HashMap<(String, String), Vec<Sample>>,
+    dropped_count: u64,
+}
+
+impl OeeAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fold one message into its station's history. Messages failing
+    /// `is_valid_operation()`, lacking a recognizable `machine_id`, or with
+    /// an unparseable timestamp are counted in `dropped_count` rather than
+    /// silently ignored, since a dashboard should be able to tell "quiet
+    /// line" from "feed is sending junk".
+    pub fn ingest(&mut self, message: &WeldingMessage) {
+        let Some((line, station)) = message.get_line_info().filter(|_| message.is_valid_operation()) else {
+            self.dropped_count += 1;
+            return;
+        };
+        let Ok(timestamp) = message.get_timestamp() else {
+            self.dropped_count += 1;
+            return;
+        };
+
+        let samples = self.stations.entry((line, station)).or_default();
+        samples.push(Sample {
+            timestamp,
+            status: message.status.clone(),
+            quality: message.quality.clone(),
+            cycle_time: message.last_cycle_time,
+        });
+        samples.sort_by_key(|sample| sample.timestamp);
+
+        let cutoff = timestamp - shift_length();
+        samples.retain(|sample| sample.timestamp >= cutoff);
+    }
+
+    /// Number of ingested messages that couldn't be attributed to a station,
+    /// because they failed `is_valid_operation()`, their `machine_id` didn't
+    /// match the `LINE-*-STATION-*` convention, or `timestamp` didn't parse.
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped_count
+    }
+
+    /// Current rolling metrics for every station seen so far.
+    pub fn snapshot(&self) -> Vec<StationStats> {
+        self.stations
+            .iter()
+            .filter_map(|((line, station), samples)| {
+                let latest = samples.last()?.timestamp;
+                Some(StationStats {
+                    line: line.clone(),
+                    station: station.clone(),
+                    trailing_15_min: window_stats(samples, latest - trailing_window()),
+                    current_shift: window_stats(samples, shift_start(latest)),
+                })
+            })
+            .collect()
+    }
+}
+
+fn shift_start(at: DateTime<Utc>) -> DateTime<Utc> {
+    let shift_seconds = shift_length().num_seconds();
+    let shift_index = at.timestamp().div_euclid(shift_seconds);
+    DateTime::from_timestamp(shift_index * shift_seconds, 0).unwrap_or(at)
+}
+
+fn window_stats(samples: &[Sample], from: DateTime<Utc>) -> WindowStats {
+    let window: Vec<&Sample> = samples.iter().filter(|sample| sample.timestamp >= from).collect();
+    if window.is_empty() {
+        return WindowStats::default();
+    }
+
+    let mut good_count = 0u64;
+    let mut scrap_count = 0u64;
+    let mut rework_count = 0u64;
+    for sample in &window {
+        match sample.quality.as_str() {
+            "good" => good_count += 1,
+            "scrap" => scrap_count += 1,
+            "rework" => rework_count += 1,
+            _ => {}
+        }
+    }
+    let total_count = good_count + scrap_count + rework_count;
+    let first_pass_yield = if total_count > 0 { good_count as f64 / total_count as f64 } else { 0.0 };
+
+    let mut cycle_times: Vec<f64> = window.iter().map(|sample| sample.cycle_time).collect();
+    let mean_cycle_time = cycle_times.iter().sum::<f64>() / cycle_times.len() as f64;
+    cycle_times.sort_by(|a, b| a.total_cmp(b));
+    let p95_cycle_time = percentile_95(&cycle_times);
+
+    let mut fault_idle_ns = 0i64;
+    let mut total_ns = 0i64;
+    for pair in window.windows(2) {
+        let elapsed = (pair[1].timestamp - pair[0].timestamp).num_nanoseconds().unwrap_or(0).max(0);
+        total_ns += elapsed;
+        if matches!(pair[0].status.as_str(), "idle" | "faulted") {
+            fault_idle_ns += elapsed;
+        }
+    }
+    let fault_idle_share = if total_ns > 0 { fault_idle_ns as f64 / total_ns as f64 } else { 0.0 };
+
+    WindowStats {
+        good_count,
+        scrap_count,
+        rework_count,
+        mean_cycle_time,
+        p95_cycle_time,
+        fault_idle_share,
+        first_pass_yield,
+    }
+}
+
+fn percentile_95(sorted_values: &[f64]) -> f64 {
+    if sorted_values.is_empty() {
+        return 0.0;
+    }
+    let rank = ((sorted_values.len() as f64) * 0.95).ceil() as usize;
+    let index = rank.saturating_sub(1).min(sorted_values.len() - 1);
+    sorted_values[index]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(machine_id: &str, timestamp: &str, status: &str, quality: &str, cycle_time: f64) -> WeldingMessage {
+        WeldingMessage {
+            machine_id: machine_id.to_string(),
+            timestamp: timestamp.to_string(),
+            status: status.to_string(),
+            last_cycle_time: cycle_time,
+            quality: quality.to_string(),
+            assembly_type: "FrameAssembly".to_string(),
+            assembly_id: "FA-001".to_string(),
+            station_id: "LINE-1-STATION-C".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_ingest_counts_invalid_messages_as_dropped() {
+        let mut acc = OeeAccumulator::new();
+        acc.ingest(&message("LINE-1-STATION-C-01", "2025-12-02T15:30:00Z", "running", "invalid_quality", 6.5));
+        assert_eq!(acc.dropped_count(), 1);
+        assert!(acc.snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_ingest_counts_unrecognized_machine_id_as_dropped() {
+        let mut acc = OeeAccumulator::new();
+        acc.ingest(&message("WELDER-07", "2025-12-02T15:30:00Z", "running", "good", 6.5));
+        assert_eq!(acc.dropped_count(), 1);
+    }
+
+    #[test]
+    fn test_snapshot_groups_by_line_and_station() {
+        let mut acc = OeeAccumulator::new();
+        acc.ingest(&message("LINE-1-STATION-C-01", "2025-12-02T15:30:00Z", "running", "good", 6.0));
+        acc.ingest(&message("LINE-1-STATION-C-02", "2025-12-02T15:30:05Z", "running", "scrap", 4.0));
+        acc.ingest(&message("LINE-2-STATION-A-01", "2025-12-02T15:30:10Z", "running", "good", 5.0));
+
+        let snapshot = acc.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        let line_1 = snapshot.iter().find(|s| s.line == "LINE-1").unwrap();
+        assert_eq!(line_1.station, "STATION-C");
+        assert_eq!(line_1.trailing_15_min.good_count, 1);
+        assert_eq!(line_1.trailing_15_min.scrap_count, 1);
+    }
+
+    #[test]
+    fn test_window_stats_compute_fpy_and_fault_idle_share() {
+        let mut acc = OeeAccumulator::new();
+        acc.ingest(&message("LINE-1-STATION-C-01", "2025-12-02T15:00:00Z", "idle", "good", 6.0));
+        acc.ingest(&message("LINE-1-STATION-C-01", "2025-12-02T15:05:00Z", "running", "good", 6.0));
+        acc.ingest(&message("LINE-1-STATION-C-01", "2025-12-02T15:10:00Z", "running", "scrap", 4.0));
+
+        let snapshot = acc.snapshot();
+        let stats = &snapshot[0].trailing_15_min;
+        assert_eq!(stats.good_count, 2);
+        assert_eq!(stats.scrap_count, 1);
+        assert!((stats.first_pass_yield - (2.0 / 3.0)).abs() < 1e-9);
+        // First 5 minutes (of 10 total) were spent idle.
+        assert!((stats.fault_idle_share - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_percentile_95_picks_high_end_of_sorted_values() {
+        let values = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        assert_eq!(percentile_95(&values), 10.0);
+    }
+}
@@ -0,0 +1,128 @@
+//! Compact binary encoding for `WeldingMessage`, generated from
+//! `proto/welding_message.proto` via `prost-build` (see `build.rs`).
+//! Bandwidth-constrained welding stations can publish this instead of JSON;
+//! `from_protobuf`/`to_protobuf` carry every field of the JSON form (the
+//! timestamp as the same instant, re-rendered as UTC RFC 3339 with
+//! nanoseconds) and `from_protobuf` runs the same `is_valid_operation` check,
+//! so downstream validation doesn't care which encoding a message arrived in.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use prost_types::Timestamp;
+
+use crate::message_parser::{ParseError, WeldingMessage};
+
+#[allow(clippy::all)]
+pub mod proto {
+    include!(concat!(env!("OUT_DIR"), "/learn_iot.welding.rs"));
+}
+
+impl WeldingMessage {
+    /// Decode a protobuf-encoded `WeldingMessage`, validating it the same way
+    /// as `parse_welding_message` does for JSON.
+    pub fn from_protobuf(bytes: &[u8]) -> Result<Self, ParseError> {
+        let wire: proto::WeldingMessage =
+            prost::Message::decode(bytes).map_err(ParseError::Protobuf)?;
+
+        let message = WeldingMessage {
+            machine_id: wire.machine_id,
+            timestamp: wire.timestamp.map(timestamp_to_rfc3339).unwrap_or_default(),
+            status: wire.status,
+            last_cycle_time: wire.last_cycle_time,
+            quality: wire.quality,
+            assembly_type: wire.assembly_type,
+            assembly_id: wire.assembly_id,
+            station_id: wire.station_id,
+        };
+
+        if !message.is_valid_operation() {
+            return Err(ParseError::InvalidOperation);
+        }
+
+        Ok(message)
+    }
+
+    /// Encode this message as protobuf bytes. `self.timestamp` is expected to
+    /// already be valid RFC 3339, as produced by the JSON path; a malformed
+    /// timestamp encodes as the Unix epoch rather than panicking.
+    pub fn to_protobuf(&self) -> Vec<u8> {
+        let wire = proto::WeldingMessage {
+            machine_id: self.machine_id.clone(),
+            timestamp: Some(rfc3339_to_timestamp(&self.timestamp)),
+            status: self.status.clone(),
+            last_cycle_time: self.last_cycle_time,
+            quality: self.quality.clone(),
+            assembly_type: self.assembly_type.clone(),
+            assembly_id: self.assembly_id.clone(),
+            station_id: self.station_id.clone(),
+        };
+
+        prost::Message::encode_to_vec(&wire)
+    }
+}
+
+fn timestamp_to_rfc3339(ts: Timestamp) -> String {
+    DateTime::from_timestamp(ts.seconds, ts.nanos.max(0) as u32)
+        .map(|dt| dt.to_rfc3339_opts(SecondsFormat::Nanos, true))
+        .unwrap_or_default()
+}
+
+fn rfc3339_to_timestamp(value: &str) -> Timestamp {
+    match DateTime::parse_from_rfc3339(value) {
+        Ok(dt) => {
+            let dt = dt.with_timezone(&Utc);
+            Timestamp {
+                seconds: dt.timestamp(),
+                nanos: dt.timestamp_subsec_nanos() as i32,
+            }
+        }
+        Err(_) => Timestamp { seconds: 0, nanos: 0 },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_message() -> WeldingMessage {
+        WeldingMessage {
+            machine_id: "LINE-1-STATION-C-01".to_string(),
+            timestamp: "2025-12-02T15:30:00Z".to_string(),
+            status: "running".to_string(),
+            last_cycle_time: 6.5,
+            quality: "scrap".to_string(),
+            assembly_type: "FrameAssembly".to_string(),
+            assembly_id: "FA-001-2025-001".to_string(),
+            station_id: "LINE-1-STATION-C".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_protobuf_round_trips_with_json_fields() {
+        let original = sample_message();
+        let bytes = original.to_protobuf();
+        let decoded = WeldingMessage::from_protobuf(&bytes).unwrap();
+
+        assert_eq!(decoded.machine_id, original.machine_id);
+        assert_eq!(decoded.status, original.status);
+        assert_eq!(decoded.last_cycle_time, original.last_cycle_time);
+        assert_eq!(decoded.quality, original.quality);
+        assert_eq!(decoded.assembly_type, original.assembly_type);
+        assert_eq!(decoded.assembly_id, original.assembly_id);
+        assert_eq!(decoded.station_id, original.station_id);
+        assert_eq!(decoded.get_timestamp().unwrap(), original.get_timestamp().unwrap());
+    }
+
+    #[test]
+    fn test_from_protobuf_rejects_invalid_operation() {
+        let mut invalid = sample_message();
+        invalid.quality = "invalid_quality".to_string();
+        let bytes = invalid.to_protobuf();
+
+        assert!(WeldingMessage::from_protobuf(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_from_protobuf_rejects_garbage_bytes() {
+        assert!(WeldingMessage::from_protobuf(&[0xFF, 0x01, 0x02]).is_err());
+    }
+}
@@ -0,0 +1,334 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::filter_logic::{
+    format_rfc3339_nanos, AssemblyDetails, LineInfo, QualityControlAlert, TriggerConditions,
+    CYCLE_TIME_THRESHOLD, SCRAP_QUALITY,
+};
+use crate::message_parser::WeldingMessage;
+
+#[cfg(test)]
+use crate::filter_logic::std_now_ns;
+
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Comparison applied between a field's actual value and a predicate's
+/// configured value.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Op {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Ne,
+}
+
+/// How a rule's predicates combine: `All` requires every predicate to match,
+/// `Any` requires at least one.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Combinator {
+    All,
+    Any,
+}
+
+/// One field-level check against a `WeldingMessage`, e.g.
+/// `{"field": "last_cycle_time", "op": "lt", "value": 7.0}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Predicate {
+    pub field: String,
+    pub op: Op,
+    pub value: Value,
+}
+
+impl Predicate {
+    fn matches(&self, message_json: &Value) -> bool {
+        let Some(actual) = message_json.get(&self.field) else {
+            return false;
+        };
+
+        if matches!(self.op, Op::Eq | Op::Ne) {
+            let equal = actual == &self.value;
+            return if self.op == Op::Eq { equal } else { !equal };
+        }
+
+        if let (Some(a), Some(b)) = (actual.as_f64(), self.value.as_f64()) {
+            return match self.op {
+                Op::Lt => a < b,
+                Op::Le => a <= b,
+                Op::Gt => a > b,
+                Op::Ge => a >= b,
+                Op::Eq | Op::Ne => unreachable!(),
+            };
+        }
+
+        if let (Some(a), Some(b)) = (actual.as_str(), self.value.as_str()) {
+            return match self.op {
+                Op::Lt => a < b,
+                Op::Le => a <= b,
+                Op::Gt => a > b,
+                Op::Ge => a >= b,
+                Op::Eq | Op::Ne => unreachable!(),
+            };
+        }
+
+        false
+    }
+}
+
+/// A named condition on a `WeldingMessage` and the alert to emit when it
+/// matches, replacing the previously hardcoded scrap/cycle-time check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    pub name: String,
+    pub combinator: Combinator,
+    pub predicates: Vec<Predicate>,
+    pub severity: String,
+    pub recommended_action: String,
+    pub alert_type: String,
+}
+
+impl Rule {
+    fn matches(&self, message_json: &Value) -> bool {
+        match self.combinator {
+            Combinator::All => self.predicates.iter().all(|p| p.matches(message_json)),
+            Combinator::Any => self.predicates.iter().any(|p| p.matches(message_json)),
+        }
+    }
+
+    fn build_alert(&self, message: &WeldingMessage, now_ns: fn() -> i64) -> QualityControlAlert {
+        let line_info = message.get_line_info().map(|(line, station)| LineInfo { line, station });
+
+        QualityControlAlert {
+            alert_type: self.alert_type.clone(),
+            source_machine: message.machine_id.clone(),
+            timestamp: format_rfc3339_nanos(now_ns()),
+            trigger_conditions: TriggerConditions {
+                quality: message.quality.clone(),
+                cycle_time: message.last_cycle_time,
+                threshold: CYCLE_TIME_THRESHOLD,
+            },
+            assembly_details: AssemblyDetails {
+                assembly_type: message.assembly_type.clone(),
+                id: message.assembly_id.clone(),
+                station_id: message.station_id.clone(),
+            },
+            severity: self.severity.clone(),
+            recommended_action: self.recommended_action.clone(),
+            line_info,
+        }
+    }
+}
+
+/// A configurable set of rules run against every incoming `WeldingMessage`,
+/// letting operators tune alerting without recompiling the filter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    /// Load a `RuleSet` from its JSON representation.
+    pub fn from_json(json_str: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json_str)
+    }
+
+    /// The built-in ruleset reproducing the original hardcoded behavior:
+    /// scrap quality with a cycle time under `CYCLE_TIME_THRESHOLD`,
+    /// severity scaled by how far below threshold the cycle time falls.
+    pub fn default_ruleset() -> Self {
+        Self {
+            rules: vec![
+                Rule {
+                    name: "scrap_fast_cycle_high".to_string(),
+                    combinator: Combinator::All,
+                    predicates: vec![
+                        Predicate { field: "quality".to_string(), op: Op::Eq, value: Value::from(SCRAP_QUALITY) },
+                        Predicate { field: "last_cycle_time".to_string(), op: Op::Le, value: Value::from(5.0) },
+                    ],
+                    severity: "high".to_string(),
+                    recommended_action: "immediate_inspection_required".to_string(),
+                    alert_type: "quality_control".to_string(),
+                },
+                Rule {
+                    name: "scrap_fast_cycle_medium".to_string(),
+                    combinator: Combinator::All,
+                    predicates: vec![
+                        Predicate { field: "quality".to_string(), op: Op::Eq, value: Value::from(SCRAP_QUALITY) },
+                        Predicate { field: "last_cycle_time".to_string(), op: Op::Gt, value: Value::from(5.0) },
+                        Predicate { field: "last_cycle_time".to_string(), op: Op::Le, value: Value::from(6.0) },
+                    ],
+                    severity: "medium".to_string(),
+                    recommended_action: "investigate_welding_parameters".to_string(),
+                    alert_type: "quality_control".to_string(),
+                },
+                Rule {
+                    name: "scrap_fast_cycle_low".to_string(),
+                    combinator: Combinator::All,
+                    predicates: vec![
+                        Predicate { field: "quality".to_string(), op: Op::Eq, value: Value::from(SCRAP_QUALITY) },
+                        Predicate { field: "last_cycle_time".to_string(), op: Op::Gt, value: Value::from(6.0) },
+                        Predicate { field: "last_cycle_time".to_string(), op: Op::Lt, value: Value::from(CYCLE_TIME_THRESHOLD) },
+                    ],
+                    severity: "low".to_string(),
+                    recommended_action: "monitor_next_cycle".to_string(),
+                    alert_type: "quality_control".to_string(),
+                },
+            ],
+        }
+    }
+
+    /// Same three-tier shape as `default_ruleset`, but built from the live,
+    /// hot-patchable `FilterConfig` instead of the compile-time
+    /// `CYCLE_TIME_THRESHOLD`/`SCRAP_QUALITY`/`SeverityBands` defaults, so a
+    /// merge/patch pushed through `filter_config` actually changes which
+    /// messages alert. Only available on `std` builds, since that's where
+    /// `FilterConfig` lives.
+    #[cfg(feature = "std")]
+    pub fn from_config(config: &crate::filter_config::FilterConfig) -> Self {
+        let threshold = config.cycle_time_threshold;
+        let high_cutoff = threshold - config.severity_bands.high_deviation;
+        let medium_cutoff = threshold - config.severity_bands.medium_deviation;
+        let scrap = config.scrap_quality.clone();
+
+        Self {
+            rules: vec![
+                Rule {
+                    name: "scrap_fast_cycle_high".to_string(),
+                    combinator: Combinator::All,
+                    predicates: vec![
+                        Predicate { field: "quality".to_string(), op: Op::Eq, value: Value::from(scrap.clone()) },
+                        Predicate { field: "last_cycle_time".to_string(), op: Op::Le, value: Value::from(high_cutoff) },
+                    ],
+                    severity: "high".to_string(),
+                    recommended_action: "immediate_inspection_required".to_string(),
+                    alert_type: "quality_control".to_string(),
+                },
+                Rule {
+                    name: "scrap_fast_cycle_medium".to_string(),
+                    combinator: Combinator::All,
+                    predicates: vec![
+                        Predicate { field: "quality".to_string(), op: Op::Eq, value: Value::from(scrap.clone()) },
+                        Predicate { field: "last_cycle_time".to_string(), op: Op::Gt, value: Value::from(high_cutoff) },
+                        Predicate { field: "last_cycle_time".to_string(), op: Op::Le, value: Value::from(medium_cutoff) },
+                    ],
+                    severity: "medium".to_string(),
+                    recommended_action: "investigate_welding_parameters".to_string(),
+                    alert_type: "quality_control".to_string(),
+                },
+                Rule {
+                    name: "scrap_fast_cycle_low".to_string(),
+                    combinator: Combinator::All,
+                    predicates: vec![
+                        Predicate { field: "quality".to_string(), op: Op::Eq, value: Value::from(scrap) },
+                        Predicate { field: "last_cycle_time".to_string(), op: Op::Gt, value: Value::from(medium_cutoff) },
+                        Predicate { field: "last_cycle_time".to_string(), op: Op::Lt, value: Value::from(threshold) },
+                    ],
+                    severity: "low".to_string(),
+                    recommended_action: "monitor_next_cycle".to_string(),
+                    alert_type: "quality_control".to_string(),
+                },
+            ],
+        }
+    }
+
+    /// Run every rule against `message`, returning one alert per matching
+    /// rule (a message may trip several rules at once). `now_ns` is forwarded
+    /// to [`Rule::build_alert`] as the alert timestamp's time source - see
+    /// [`crate::filter_logic::generate_quality_alert`] for why it's injected
+    /// rather than read from `chrono::Utc::now()` directly.
+    pub fn evaluate(&self, message: &WeldingMessage, now_ns: fn() -> i64) -> Vec<QualityControlAlert> {
+        let message_json = serde_json::to_value(message).unwrap_or(Value::Null);
+
+        self.rules
+            .iter()
+            .filter(|rule| rule.matches(&message_json))
+            .map(|rule| rule.build_alert(message, now_ns))
+            .collect()
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::default_ruleset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_message(quality: &str, cycle_time: f64) -> WeldingMessage {
+        WeldingMessage {
+            machine_id: "LINE-1-STATION-C-01".to_string(),
+            timestamp: "2025-12-02T15:30:00Z".to_string(),
+            status: "running".to_string(),
+            last_cycle_time: cycle_time,
+            quality: quality.to_string(),
+            assembly_type: "FrameAssembly".to_string(),
+            assembly_id: "FA-001-2025-001".to_string(),
+            station_id: "LINE-1-STATION-C".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_default_ruleset_matches_original_thresholds() {
+        let ruleset = RuleSet::default_ruleset();
+
+        assert_eq!(ruleset.evaluate(&create_test_message("scrap", 4.5), std_now_ns).len(), 1);
+        assert_eq!(ruleset.evaluate(&create_test_message("scrap", 4.5), std_now_ns)[0].severity, "high");
+
+        assert_eq!(ruleset.evaluate(&create_test_message("scrap", 5.5), std_now_ns)[0].severity, "medium");
+        assert_eq!(ruleset.evaluate(&create_test_message("scrap", 6.8), std_now_ns)[0].severity, "low");
+
+        assert!(ruleset.evaluate(&create_test_message("scrap", 7.5), std_now_ns).is_empty());
+        assert!(ruleset.evaluate(&create_test_message("good", 6.0), std_now_ns).is_empty());
+    }
+
+    #[test]
+    fn test_custom_ruleset_can_trip_multiple_rules() {
+        let ruleset = RuleSet {
+            rules: vec![
+                Rule {
+                    name: "any_scrap".to_string(),
+                    combinator: Combinator::All,
+                    predicates: vec![Predicate {
+                        field: "quality".to_string(),
+                        op: Op::Eq,
+                        value: Value::from("scrap"),
+                    }],
+                    severity: "low".to_string(),
+                    recommended_action: "log_only".to_string(),
+                    alert_type: "scrap_observed".to_string(),
+                },
+                Rule {
+                    name: "scrap_or_slow".to_string(),
+                    combinator: Combinator::Any,
+                    predicates: vec![
+                        Predicate { field: "quality".to_string(), op: Op::Eq, value: Value::from("scrap") },
+                        Predicate { field: "last_cycle_time".to_string(), op: Op::Ge, value: Value::from(9.0) },
+                    ],
+                    severity: "medium".to_string(),
+                    recommended_action: "review".to_string(),
+                    alert_type: "throughput_risk".to_string(),
+                },
+            ],
+        };
+
+        let alerts = ruleset.evaluate(&create_test_message("scrap", 8.0), std_now_ns);
+        assert_eq!(alerts.len(), 2);
+    }
+
+    #[test]
+    fn test_ruleset_json_round_trip() {
+        let ruleset = RuleSet::default_ruleset();
+        let json = serde_json::to_string(&ruleset).unwrap();
+        let loaded = RuleSet::from_json(&json).unwrap();
+        assert_eq!(loaded.rules.len(), ruleset.rules.len());
+    }
+}
@@ -0,0 +1,239 @@
+//! A streaming consumer for live welding feeds - newline-delimited JSON, or
+//! length-prefixed protobuf frames (see `protobuf`) - over any `Read`. Unlike
+//! `parse_welding_message`, which handles one string at a time, this is meant
+//! to sit on a long-lived socket inside a line-monitoring daemon: it exposes
+//! the underlying descriptor so callers can register it in their own
+//! `select`/`epoll` event loop, and a non-blocking `poll_for_message` for
+//! drawing frames out of it as they arrive.
+
+use std::io::Read;
+
+#[cfg(unix)]
+use std::os::unix::io::{AsRawFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsRawSocket, RawSocket};
+
+use crate::message_parser::{parse_welding_message, ParseError, WeldingMessage};
+
+/// Why reading or decoding one frame from a `WeldingStream` failed. A frame
+/// failing doesn't end the stream - `WeldingStream` resumes at the next
+/// frame boundary afterward.
+#[derive(Debug)]
+pub enum StreamError {
+    Io(std::io::Error),
+    Utf8(std::str::Utf8Error),
+    Parse(ParseError),
+}
+
+impl std::fmt::Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "{}", e),
+            StreamError::Utf8(e) => write!(f, "{}", e),
+            StreamError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+/// How frames are delimited on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameFormat {
+    /// One JSON-encoded `WeldingMessage` per line.
+    NewlineDelimitedJson,
+    /// A 4-byte big-endian length prefix followed by that many bytes of
+    /// `WeldingMessage::to_protobuf`-encoded payload.
+    LengthPrefixedProtobuf,
+}
+
+enum ReadOutcome {
+    Read,
+    WouldBlock,
+    Eof,
+}
+
+/// A `WeldingMessage` source backed by any `Read` - a `TcpStream`, an MQTT
+/// bridge's pipe, a file replaying a shift's feed, etc.
+pub struct WeldingStream<R> {
+    source: R,
+    buffer: Vec<u8>,
+    format: FrameFormat,
+    eof: bool,
+}
+
+impl<R: Read> WeldingStream<R> {
+    pub fn new(source: R, format: FrameFormat) -> Self {
+        Self { source, buffer: Vec::new(), format, eof: false }
+    }
+
+    /// Return one complete message, reading from `source` as needed, or
+    /// `Ok(None)` if no complete frame has arrived yet because `source`
+    /// reported `WouldBlock` or reached EOF. A blocking `source` blocks here
+    /// until it has data.
+    pub fn poll_for_message(&mut self) -> Result<Option<WeldingMessage>, StreamError> {
+        loop {
+            if let Some(message) = self.take_frame()? {
+                return Ok(Some(message));
+            }
+
+            match self.read_into_buffer()? {
+                ReadOutcome::Read => continue,
+                ReadOutcome::WouldBlock | ReadOutcome::Eof => return Ok(None),
+            }
+        }
+    }
+
+    /// Try to extract one complete frame from the buffer already read,
+    /// without performing any I/O. Blank lines in the newline-delimited
+    /// format are skipped rather than treated as frames.
+    fn take_frame(&mut self) -> Result<Option<WeldingMessage>, StreamError> {
+        match self.format {
+            FrameFormat::NewlineDelimitedJson => loop {
+                let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') else {
+                    return Ok(None);
+                };
+                let mut line: Vec<u8> = self.buffer.drain(..=pos).collect();
+                line.pop(); // trailing '\n'
+                if line.last() == Some(&b'\r') {
+                    line.pop();
+                }
+                if line.iter().all(u8::is_ascii_whitespace) {
+                    continue;
+                }
+
+                let text = std::str::from_utf8(&line).map_err(StreamError::Utf8)?;
+                return parse_welding_message(text).map(Some).map_err(StreamError::Parse);
+            },
+            FrameFormat::LengthPrefixedProtobuf => {
+                if self.buffer.len() < 4 {
+                    return Ok(None);
+                }
+                let len = u32::from_be_bytes(self.buffer[..4].try_into().expect("checked length")) as usize;
+                if self.buffer.len() < 4 + len {
+                    return Ok(None);
+                }
+
+                let frame: Vec<u8> = self.buffer.drain(..4 + len).collect();
+                WeldingMessage::from_protobuf(&frame[4..]).map(Some).map_err(StreamError::Parse)
+            }
+        }
+    }
+
+    /// Read one chunk from `source` into the buffer, tolerating `EINTR` and
+    /// reporting `WouldBlock` instead of erroring so non-blocking sources can
+    /// be driven from an external event loop.
+    fn read_into_buffer(&mut self) -> Result<ReadOutcome, StreamError> {
+        if self.eof {
+            return Ok(ReadOutcome::Eof);
+        }
+
+        let mut chunk = [0u8; 4096];
+        loop {
+            match self.source.read(&mut chunk) {
+                Ok(0) => {
+                    self.eof = true;
+                    return Ok(ReadOutcome::Eof);
+                }
+                Ok(n) => {
+                    self.buffer.extend_from_slice(&chunk[..n]);
+                    return Ok(ReadOutcome::Read);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok(ReadOutcome::WouldBlock),
+                Err(e) => return Err(StreamError::Io(e)),
+            }
+        }
+    }
+}
+
+/// Blocking-style consumption: repeatedly calls `read` on `source` until a
+/// frame is available or it reaches true EOF. For a non-blocking `source`,
+/// prefer driving `poll_for_message` from your own event loop instead, since
+/// a `WouldBlock` here just causes a retry rather than yielding control.
+impl<R: Read> Iterator for WeldingStream<R> {
+    type Item = Result<WeldingMessage, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.take_frame() {
+                Ok(Some(message)) => return Some(Ok(message)),
+                Ok(None) => {}
+                Err(e) => return Some(Err(e)),
+            }
+
+            match self.read_into_buffer() {
+                Ok(ReadOutcome::Read) | Ok(ReadOutcome::WouldBlock) => continue,
+                Ok(ReadOutcome::Eof) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<R: AsRawFd> AsRawFd for WeldingStream<R> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.source.as_raw_fd()
+    }
+}
+
+#[cfg(windows)]
+impl<R: AsRawSocket> AsRawSocket for WeldingStream<R> {
+    fn as_raw_socket(&self) -> RawSocket {
+        self.source.as_raw_socket()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn sample_json(machine_id: &str) -> String {
+        format!(
+            r#"{{"machine_id": "{machine_id}", "timestamp": "2025-12-02T15:30:00Z", "status": "running", "last_cycle_time": 6.5, "quality": "scrap", "assembly_type": "FrameAssembly", "assembly_id": "FA-001", "station_id": "LINE-1-STATION-C"}}"#
+        )
+    }
+
+    #[test]
+    fn test_iterator_yields_newline_delimited_messages() {
+        let feed = format!("{}\n{}\n", sample_json("LINE-1-STATION-C-01"), sample_json("LINE-1-STATION-C-02"));
+        let stream = WeldingStream::new(Cursor::new(feed.into_bytes()), FrameFormat::NewlineDelimitedJson);
+
+        let messages: Vec<_> = stream.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].machine_id, "LINE-1-STATION-C-01");
+        assert_eq!(messages[1].machine_id, "LINE-1-STATION-C-02");
+    }
+
+    #[test]
+    fn test_iterator_isolates_one_bad_frame() {
+        let feed = format!("not json\n{}\n", sample_json("LINE-1-STATION-C-01"));
+        let stream = WeldingStream::new(Cursor::new(feed.into_bytes()), FrameFormat::NewlineDelimitedJson);
+
+        let results: Vec<_> = stream.collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_err());
+        assert!(results[1].is_ok());
+    }
+
+    #[test]
+    fn test_poll_for_message_returns_none_until_a_full_line_arrives() {
+        let mut stream = WeldingStream::new(Cursor::new(Vec::new()), FrameFormat::NewlineDelimitedJson);
+        assert!(stream.poll_for_message().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_length_prefixed_protobuf_round_trip() {
+        let message = parse_welding_message(&sample_json("LINE-1-STATION-C-01")).unwrap();
+        let payload = message.to_protobuf();
+        let mut framed = (payload.len() as u32).to_be_bytes().to_vec();
+        framed.extend_from_slice(&payload);
+
+        let stream = WeldingStream::new(Cursor::new(framed), FrameFormat::LengthPrefixedProtobuf);
+        let messages: Vec<_> = stream.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].machine_id, "LINE-1-STATION-C-01");
+    }
+}